@@ -0,0 +1,33 @@
+// One benchmark group per implemented day and part, solving the puzzle input in data/.
+// Days without an input are skipped, run `cargo run fetch all` to download them.
+use aoc_2020::days::{self, DAYS};
+use aoc_2020::io::{RunCode, RunOptions};
+use aoc_2020::problem::Part;
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+fn bench_days(c: &mut Criterion) {
+    let options = RunOptions::default();
+    for day in DAYS {
+        let run_code = RunCode::puzzle(day.year, day.problem);
+        let Ok((input, ctx)) = run_code.get_input_and_context(&options) else {
+            eprintln!("Skipping {}, there is no input", run_code.label());
+            continue;
+        };
+        let solution = days::get_solution(day.year, day.problem).unwrap();
+        for part in [Part::A, Part::B] {
+            if !day.is_implemented(part) {
+                continue;
+            }
+            let mut group = c.benchmark_group(format!("{}-day{:02}{part}", day.year, day.problem));
+            // Parsing is included, as it is when running a single part
+            group.bench_function("solve", |b| {
+                b.iter(|| solution.solve(part, black_box(&input), &ctx))
+            });
+            group.finish();
+        }
+    }
+}
+
+criterion_group!(benches, bench_days);
+criterion_main!(benches);
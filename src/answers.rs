@@ -0,0 +1,156 @@
+use std::fs;
+use std::path::Path;
+
+use crate::io::AocRunError;
+use crate::problem::{Answer, Part};
+
+// Expected answers are stored one per line, e.g.
+// A: 514579
+// B: 241861950
+// Either line may be omitted if that answer is not yet known.
+// A multi-line answer starts on the next line and each of its lines is indented, e.g.
+// B:
+//   #..#
+//   ####
+const BLOCK_INDENT: &str = "  ";
+
+#[derive(Debug, Default)]
+pub struct ExpectedAnswers {
+    answer_a: Option<String>,
+    answer_b: Option<String>,
+}
+
+impl ExpectedAnswers {
+    // A missing file is not an error, it just means nothing can be verified
+    pub fn load(path: &str) -> Result<Self, AocRunError> {
+        let Ok(contents) = fs::read_to_string(path) else {
+            return Ok(Self::default());
+        };
+        Self::parse(&contents).ok_or_else(|| AocRunError::BadAnswerFile(path.to_string()))
+    }
+
+    fn parse(contents: &str) -> Option<Self> {
+        let mut answers = Self::default();
+        let mut block: Option<(Part, Vec<&str>)> = None;
+        for line in contents.lines() {
+            if let Some(block_line) = line.strip_prefix(BLOCK_INDENT) {
+                block.as_mut()?.1.push(block_line);
+                continue;
+            }
+            if let Some((part, lines)) = block.take() {
+                answers.set(part, lines.join("\n"));
+            }
+            // Blank lines between or after the answers are allowed
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (part, answer) = line.split_once(':')?;
+            let part = match part {
+                "A" => Part::A,
+                "B" => Part::B,
+                _ => return None,
+            };
+            match answer.strip_prefix(' ') {
+                Some(answer) if !answer.is_empty() => answers.set(part, answer.to_string()),
+                _ if answer.trim().is_empty() => block = Some((part, vec![])),
+                _ => return None,
+            }
+        }
+        if let Some((part, lines)) = block {
+            answers.set(part, lines.join("\n"));
+        }
+        Some(answers)
+    }
+
+    pub fn get(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::A => self.answer_a.as_deref(),
+            Part::B => self.answer_b.as_deref(),
+        }
+    }
+
+    pub fn set(&mut self, part: Part, answer: String) {
+        match part {
+            Part::A => self.answer_a = Some(answer),
+            Part::B => self.answer_b = Some(answer),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let contents: String = [Part::A, Part::B]
+            .into_iter()
+            .filter_map(|part| {
+                let answer = self.get(part)?;
+                if !answer.contains('\n') {
+                    return Some(format!("{part}: {answer}\n"));
+                }
+                let lines: String = answer
+                    .lines()
+                    .map(|line| format!("{BLOCK_INDENT}{line}\n"))
+                    .collect();
+                Some(format!("{part}:\n{lines}"))
+            })
+            .collect();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, contents)
+    }
+
+    pub fn check(&self, part: Part, answer: &Answer) -> AnswerCheck {
+        match self.get(part) {
+            None => AnswerCheck::Unverified,
+            Some(expected) if answer.matches(expected) => AnswerCheck::Correct,
+            Some(expected) => AnswerCheck::Wrong(expected.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnswerCheck {
+    Correct,
+    Wrong(String),
+    Unverified,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn reads_and_writes_blocks() {
+        let contents = "A: 0123\nB:\n  #..#\n  ####\n";
+        let answers = ExpectedAnswers::parse(contents).unwrap();
+        assert_eq!(answers.get(Part::A), Some("0123"));
+        assert_eq!(answers.get(Part::B), Some("#..#\n####"));
+        assert_eq!(
+            answers.check(Part::A, &Answer::Integer(123)),
+            AnswerCheck::Correct
+        );
+        assert_eq!(
+            answers.check(Part::B, &Answer::Block("#..#\n####".to_string())),
+            AnswerCheck::Correct
+        );
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("answers").join("08.txt");
+        answers.save(&path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), contents);
+
+        assert!(ExpectedAnswers::parse("C: 1\n").is_none());
+        assert!(ExpectedAnswers::parse("  #..#\n").is_none());
+        assert!(ExpectedAnswers::parse("A:1\n").is_none());
+    }
+
+    #[test]
+    fn skips_blank_lines() {
+        let answers = ExpectedAnswers::parse("A: 1\n\nB: 2\n").unwrap();
+        assert_eq!(answers.get(Part::A), Some("1"));
+        assert_eq!(answers.get(Part::B), Some("2"));
+
+        let answers = ExpectedAnswers::parse("A:\n  #.\n\nB: 2\n\n").unwrap();
+        assert_eq!(answers.get(Part::A), Some("#."));
+        assert_eq!(answers.get(Part::B), Some("2"));
+    }
+}
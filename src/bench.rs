@@ -0,0 +1,121 @@
+use colored::Colorize;
+use std::fmt::Display;
+use std::time::Duration;
+
+use crate::problem::Part;
+
+#[derive(Debug, Clone, Copy)]
+pub struct BenchStats {
+    iterations: usize,
+    min: Duration,
+    median: Duration,
+    mean: Duration,
+    stddev: Duration,
+}
+
+impl BenchStats {
+    // Returns None if there are no samples to summarise
+    pub fn from_samples(mut samples: Vec<Duration>) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort_unstable();
+        let n = samples.len();
+        let median = if n.is_multiple_of(2) {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        } else {
+            samples[n / 2]
+        };
+        let mean_secs = samples.iter().map(|d| d.as_secs_f64()).sum::<f64>() / n as f64;
+        // Sample standard deviation, so a single run has no spread
+        let variance = if n > 1 {
+            samples
+                .iter()
+                .map(|d| (d.as_secs_f64() - mean_secs).powi(2))
+                .sum::<f64>()
+                / (n - 1) as f64
+        } else {
+            0.0
+        };
+        Some(Self {
+            iterations: n,
+            min: samples[0],
+            median,
+            mean: Duration::from_secs_f64(mean_secs),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+impl Display for BenchStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "min {:.2?} | median {:.2?} | mean {:.2?} | stddev {:.2?} ({} runs)",
+            self.min, self.median, self.mean, self.stddev, self.iterations
+        )
+    }
+}
+
+pub struct BenchReport {
+    // Only for solutions that parse their input once, ahead of both parts
+    parse: Option<BenchStats>,
+    parts: Vec<(Part, Option<BenchStats>)>,
+}
+
+impl BenchReport {
+    pub fn new(parse: Option<BenchStats>, parts: Vec<(Part, Option<BenchStats>)>) -> Self {
+        Self { parse, parts }
+    }
+}
+
+impl Display for BenchReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(stats) = self.parse {
+            writeln!(f, "Parse : {}", stats.to_string().cyan())?
+        }
+        for (part, stats) in self.parts.iter() {
+            let stats_str = match stats {
+                Some(stats) => stats.to_string().bold().cyan(),
+                None => "TODO".to_string().dimmed(),
+            };
+            writeln!(f, "{part} : {stats_str}")?
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(samples: &[u64]) -> Vec<Duration> {
+        samples
+            .iter()
+            .map(|&ms| Duration::from_millis(ms))
+            .collect()
+    }
+
+    #[test]
+    fn summarises_samples() {
+        assert!(BenchStats::from_samples(vec![]).is_none());
+
+        let stats = BenchStats::from_samples(millis(&[7])).unwrap();
+        assert_eq!(stats.iterations, 1);
+        assert_eq!(stats.min, Duration::from_millis(7));
+        assert_eq!(stats.median, Duration::from_millis(7));
+        assert_eq!(stats.stddev, Duration::ZERO);
+
+        // Samples arrive unsorted
+        let stats = BenchStats::from_samples(millis(&[9, 1, 5])).unwrap();
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(5));
+        assert_eq!(stats.mean, Duration::from_millis(5));
+        assert_eq!(stats.stddev, Duration::from_millis(4));
+
+        // An even count takes the mean of the middle two
+        let stats = BenchStats::from_samples(millis(&[4, 1, 2, 10])).unwrap();
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.iterations, 4);
+    }
+}
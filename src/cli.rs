@@ -0,0 +1,177 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::context::{LogLevel, Params};
+use crate::io::{self, InputOverride, RunOptions};
+use crate::normalize::Normalization;
+use crate::report::OutputFormat;
+
+pub const DEFAULT_BENCH_ITERATIONS: usize = 10;
+
+pub const USAGE: &str = "Usage: [options] [codes...] | [options] bench <codes> [iterations]
+       [options] fetch <codes> | [options] submit <day><part>
+       [options] puzzle <day> [<block> [<name>]] | [options] new <day>
+       list [tag]
+Options:
+    --year <year>      Year for run codes that don't name one (default 2020)
+    --input <path>     Read the puzzle input from <path>, or from stdin if <path> is -
+    --data-dir <dir>   Look for inputs, examples and answers in <dir> instead of ./data
+    --format <format>  Print results as text (default), json or csv
+    --jobs <n>         Run days and parts on <n> threads, results are still printed in order
+    --param <name=n>   Set a puzzle parameter, e.g. lookback=5, overriding defaults and example headers
+    --log <level>      Print solutions' logs at <level> or above: error, warn (default), info,
+                       debug or trace
+    --visualize        Let solutions draw their visualizations, e.g. day 11's seating
+    --normalize <list> Tidy inputs before solving with a comma separated list of crlf (CRLF to
+                       LF), trim (trailing whitespace), newline (exactly one final newline)
+                       and no-newline (no final newline), or none (default crlf,trim,newline)
+    --memory           Show the peak memory and number of allocations of each part
+    --timeout <secs>   Give up on any part still running after <secs>, e.g. 0.5, and report a timeout
+    --watch            Keep running, and run again whenever an input or answer file changes";
+
+pub enum Command {
+    Run(Vec<String>),
+    Bench(String, usize),
+    Fetch(Vec<String>),
+    Submit(String),
+    // Optionally saves the numbered code block as an example, with an optional name
+    Puzzle(String, Option<(usize, Option<String>)>),
+    New(String),
+    // Optionally only the days with the given tag
+    List(Option<String>),
+}
+
+pub struct Cli {
+    pub command: Command,
+    pub options: RunOptions,
+    pub format: OutputFormat,
+    pub watch: bool,
+}
+
+impl Cli {
+    pub fn parse(args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = RunOptions::default();
+        let mut format = OutputFormat::Text;
+        let mut watch = false;
+        let mut positional = vec![];
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut flag_value = || {
+                args.next()
+                    .ok_or_else(|| format!("Missing value for {arg}"))
+            };
+            match arg.as_str() {
+                "--input" => {
+                    options.input = Some(match flag_value()?.as_str() {
+                        "-" => InputOverride::Stdin,
+                        path => InputOverride::File(PathBuf::from(path)),
+                    })
+                }
+                "--data-dir" => options.data_dir = PathBuf::from(flag_value()?),
+                "--year" => {
+                    let year = flag_value()?;
+                    options.year = year
+                        .parse()
+                        .map_err(|_e| format!("Could not parse year \"{year}\""))?
+                }
+                "--jobs" => {
+                    let jobs = flag_value()?;
+                    options.jobs = match jobs.parse() {
+                        Ok(jobs) if jobs > 0 => jobs,
+                        _ => return Err(format!("Could not parse job count \"{jobs}\"")),
+                    }
+                }
+                "--param" => {
+                    let assignment = flag_value()?;
+                    let (name, value) = Params::parse_assignment(&assignment)
+                        .ok_or_else(|| format!("Could not parse parameter \"{assignment}\""))?;
+                    options.params.set(&name, value)
+                }
+                "--log" => {
+                    let level = flag_value()?;
+                    options.log_level = LogLevel::parse(&level)
+                        .ok_or_else(|| format!("Unknown log level \"{level}\""))?
+                }
+                "--visualize" => options.visualize = true,
+                "--normalize" => {
+                    let steps = flag_value()?;
+                    options.normalization = Normalization::parse(&steps)
+                        .ok_or_else(|| format!("Could not parse normalization \"{steps}\""))?
+                }
+                "--memory" => options.memory = true,
+                "--timeout" => {
+                    let timeout = flag_value()?;
+                    options.timeout = match timeout.parse::<f64>() {
+                        Ok(secs) if secs > 0.0 && secs.is_finite() => {
+                            Some(Duration::from_secs_f64(secs))
+                        }
+                        _ => return Err(format!("Could not parse timeout \"{timeout}\"")),
+                    }
+                }
+                "--watch" => watch = true,
+                "--format" => {
+                    let value = flag_value()?;
+                    format = OutputFormat::parse(&value)
+                        .ok_or_else(|| format!("Unknown output format \"{value}\""))?
+                }
+                flag if flag.starts_with("--") => {
+                    return Err(format!("Unknown option {flag}\n{USAGE}"))
+                }
+                _ => positional.push(arg),
+            }
+        }
+
+        let command = match positional.first().map(String::as_str) {
+            Some("bench") => match &positional[1..] {
+                [codes] => Command::Bench(codes.clone(), DEFAULT_BENCH_ITERATIONS),
+                [codes, iterations] => match iterations.parse() {
+                    Ok(iterations) if iterations > 0 => Command::Bench(codes.clone(), iterations),
+                    _ => return Err(format!("Could not parse iteration count \"{iterations}\"")),
+                },
+                _ => return Err(USAGE.to_string()),
+            },
+            Some("fetch") if positional.len() > 1 => Command::Fetch(positional[1..].to_vec()),
+            Some("fetch") => return Err(USAGE.to_string()),
+            Some("submit") => match &positional[1..] {
+                [code] => Command::Submit(code.clone()),
+                _ => return Err(USAGE.to_string()),
+            },
+            Some("puzzle") => match &positional[1..] {
+                [code] => Command::Puzzle(code.clone(), None),
+                [code, block, name @ ..] if name.len() <= 1 => {
+                    let name = name.first().cloned();
+                    // The name becomes part of a file name, and must be selectable with eN:<name>
+                    if let Some(name) = name.as_ref().filter(|name| !io::is_example_name(name)) {
+                        return Err(format!(
+                            "Example names can only use letters, digits, _ and -, not \"{name}\""
+                        ));
+                    }
+                    match block.parse() {
+                        Ok(block) if block > 0 => {
+                            Command::Puzzle(code.clone(), Some((block, name)))
+                        }
+                        _ => return Err(format!("Could not parse block number \"{block}\"")),
+                    }
+                }
+                _ => return Err(USAGE.to_string()),
+            },
+            Some("new") => match &positional[1..] {
+                [code] => Command::New(code.clone()),
+                _ => return Err(USAGE.to_string()),
+            },
+            Some("list") => match &positional[1..] {
+                [] => Command::List(None),
+                [tag] => Command::List(Some(tag.clone())),
+                _ => return Err(USAGE.to_string()),
+            },
+            None => Command::Run(vec!["all".to_string()]),
+            Some(_) => Command::Run(positional),
+        };
+        Ok(Self {
+            command,
+            options,
+            format,
+            watch,
+        })
+    }
+}
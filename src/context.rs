@@ -0,0 +1,352 @@
+// What the runner passes to a solution alongside its input: its parameters, somewhere to
+// send logs and visualizations, and a flag that asks it to stop early
+use colored::Colorize;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::days;
+use crate::problem::SolveError;
+use crate::watch::CLEAR_SCREEN;
+
+// Lines at the start of an input that set parameters for it, e.g. `#! lookback=5`
+const HEADER_PREFIX: &str = "#!";
+
+// Named integer parameters for a run, such as how far day 9 looks back.
+// Each day has defaults, which an example's header or --param can override.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params(BTreeMap<String, i64>);
+
+impl Params {
+    // The defaults for a day, from its entry in the register_days! table
+    pub fn for_day(year: u16, problem: usize) -> Self {
+        let mut params = Self::default();
+        if let Some(day) = days::find(year, problem) {
+            for (name, value) in day.params {
+                params.set(name, *value);
+            }
+        }
+        params
+    }
+
+    pub fn get(&self, name: &str) -> Option<i64> {
+        self.0.get(name).copied()
+    }
+
+    pub fn set(&mut self, name: &str, value: i64) {
+        self.0.insert(name.to_string(), value);
+    }
+
+    // Later values win, so overrides are applied in order of precedence
+    pub fn extend(&mut self, other: &Params) {
+        self.0.extend(other.0.iter().map(|(k, v)| (k.clone(), *v)));
+    }
+
+    // Reads an assignment such as "lookback=5"
+    pub fn parse_assignment(assignment: &str) -> Option<(String, i64)> {
+        let (name, value) = assignment.split_once('=')?;
+        let name = name.trim();
+        let valid_name =
+            !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_name {
+            return None;
+        }
+        Some((name.to_string(), value.trim().parse().ok()?))
+    }
+
+    // Splits the header lines off the start of an input, returning the parameters they set
+    // and the rest of the input. Returns the bad line if the header can't be read.
+    pub fn split_header(input: &str) -> Result<(Params, &str), String> {
+        let mut params = Params::default();
+        let mut rest = input;
+        while let Some(header) = rest.strip_prefix(HEADER_PREFIX) {
+            let (line, after) = header.split_once('\n').unwrap_or((header, ""));
+            for assignment in line.split_whitespace() {
+                let (name, value) = Self::parse_assignment(assignment)
+                    .ok_or_else(|| format!("{HEADER_PREFIX}{line}"))?;
+                params.set(&name, value);
+            }
+            rest = after;
+        }
+        Ok((params, rest))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Error,
+    #[default]
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub fn parse(level: &str) -> Option<Self> {
+        match level {
+            "error" => Some(LogLevel::Error),
+            "warn" => Some(LogLevel::Warn),
+            "info" => Some(LogLevel::Info),
+            "debug" => Some(LogLevel::Debug),
+            "trace" => Some(LogLevel::Trace),
+            _ => None,
+        }
+    }
+}
+
+impl Display for LogLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let level = match self {
+            LogLevel::Error => "error".red(),
+            LogLevel::Warn => "warn".yellow(),
+            LogLevel::Info => "info".green(),
+            LogLevel::Debug => "debug".blue(),
+            LogLevel::Trace => "trace".dimmed(),
+        };
+        level.fmt(f)
+    }
+}
+
+// Where a solution's logs and visualization frames end up
+pub trait Sink: Send + Sync {
+    fn log(&self, level: LogLevel, message: &str);
+    fn frame(&self, frame: &str);
+}
+
+// How long each frame stays on screen, so that the visualization can be followed
+const FRAME_DELAY: Duration = Duration::from_millis(100);
+
+// Writes to stderr, leaving stdout for the results
+pub struct TerminalSink {
+    // The run code, so that logs from different days can be told apart
+    label: String,
+}
+
+impl TerminalSink {
+    pub fn new(label: String) -> Self {
+        Self { label }
+    }
+}
+
+impl Sink for TerminalSink {
+    fn log(&self, level: LogLevel, message: &str) {
+        eprintln!("[{} {level}] {message}", self.label);
+    }
+
+    fn frame(&self, frame: &str) {
+        eprintln!("{CLEAR_SCREEN}{frame}");
+        thread::sleep(FRAME_DELAY);
+    }
+}
+
+pub struct Context {
+    params: Params,
+    log_level: LogLevel,
+    visualize: bool,
+    sink: Arc<dyn Sink>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self::new(Params::default())
+    }
+}
+
+impl Context {
+    pub fn new(params: Params) -> Self {
+        Self {
+            params,
+            log_level: LogLevel::default(),
+            visualize: false,
+            sink: Arc::new(TerminalSink::new(String::new())),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn with_output(
+        mut self,
+        log_level: LogLevel,
+        visualize: bool,
+        sink: Box<dyn Sink>,
+    ) -> Self {
+        self.log_level = log_level;
+        self.visualize = visualize;
+        self.sink = sink.into();
+        self
+    }
+
+    // A copy with its own cancellation flag, so that one part can be stopped without the other
+    pub fn for_worker(&self) -> Self {
+        Self {
+            params: self.params.clone(),
+            log_level: self.log_level,
+            visualize: self.visualize,
+            sink: self.sink.clone(),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    // A parameter that the day must have a default for
+    pub fn param<T: TryFrom<i64>>(&self, name: &str) -> Result<T, SolveError> {
+        let value = self
+            .params
+            .get(name)
+            .ok_or_else(|| SolveError::MissingParam(name.to_string()))?;
+        T::try_from(value).map_err(|_e| {
+            SolveError::UnsupportedInput(format!("parameter {name}={value} is out of range"))
+        })
+    }
+
+    pub fn log_enabled(&self, level: LogLevel) -> bool {
+        level <= self.log_level
+    }
+
+    // Pass format_args! so that nothing is formatted unless the level is enabled. The
+    // arguments are still evaluated, so check log_enabled first if they are costly.
+    pub fn log(&self, level: LogLevel, message: impl Display) {
+        if self.log_enabled(level) {
+            self.sink.log(level, &message.to_string());
+        }
+    }
+
+    pub fn info(&self, message: impl Display) {
+        self.log(LogLevel::Info, message)
+    }
+
+    pub fn debug(&self, message: impl Display) {
+        self.log(LogLevel::Debug, message)
+    }
+
+    pub fn frame(&self, frame: impl Display) {
+        if self.visualize {
+            self.sink.frame(&frame.to_string());
+        }
+    }
+
+    // Asks the solution to stop at its next call to check_cancelled
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    // Long running loops should call this now and then
+    pub fn check_cancelled(&self) -> Result<(), SolveError> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            Err(SolveError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_headers() {
+        let (params, input) = Params::split_header("#! lookback=5\n35\n20\n").unwrap();
+        assert_eq!(params.get("lookback"), Some(5));
+        assert_eq!(input, "35\n20\n");
+
+        let (params, input) =
+            Params::split_header("#! turns_a=10 turns_b=20\n#!cycles=1\n0,3,6").unwrap();
+        assert_eq!(params.get("turns_a"), Some(10));
+        assert_eq!(params.get("turns_b"), Some(20));
+        assert_eq!(params.get("cycles"), Some(1));
+        assert_eq!(input, "0,3,6");
+
+        // Grids start with '#' but never with the header prefix
+        let (params, input) = Params::split_header("#.#\n...\n").unwrap();
+        assert_eq!(params, Params::default());
+        assert_eq!(input, "#.#\n...\n");
+
+        assert_eq!(
+            Params::split_header("#! lookback=five\n35\n"),
+            Err("#! lookback=five".to_string())
+        );
+    }
+
+    #[test]
+    fn reads_params_in_order_of_precedence() {
+        let mut params = Params::for_day(2020, 9);
+        assert_eq!(params.get("lookback"), Some(25));
+        let (header, _input) = Params::split_header("#! lookback=5\n").unwrap();
+        params.extend(&header);
+        let ctx = Context::new(params.clone());
+        assert_eq!(ctx.param::<usize>("lookback").unwrap(), 5);
+
+        let (name, value) = Params::parse_assignment("lookback=7").unwrap();
+        params.set(&name, value);
+        let ctx = Context::new(params);
+        assert_eq!(ctx.param::<usize>("lookback").unwrap(), 7);
+        assert!(matches!(
+            ctx.param::<usize>("cycles"),
+            Err(SolveError::MissingParam(_))
+        ));
+
+        let ctx = Context::new(Params::split_header("#! lookback=-1\n").unwrap().0);
+        assert!(ctx.param::<usize>("lookback").is_err());
+        assert!(Params::parse_assignment("look back=1").is_none());
+        assert!(Params::parse_assignment("lookback").is_none());
+    }
+
+    #[derive(Default)]
+    struct RecordingSink {
+        output: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    impl Sink for RecordingSink {
+        fn log(&self, level: LogLevel, message: &str) {
+            let level = format!("{level:?}");
+            self.output
+                .lock()
+                .unwrap()
+                .push(format!("{level} {message}"));
+        }
+
+        fn frame(&self, frame: &str) {
+            self.output.lock().unwrap().push(format!("frame {frame}"));
+        }
+    }
+
+    #[test]
+    fn filters_output() {
+        let sink = RecordingSink::default();
+        let output = sink.output.clone();
+        let ctx = Context::default().with_output(LogLevel::Debug, false, Box::new(sink));
+        ctx.info(format_args!("round {}", 1));
+        ctx.debug("occupied 37");
+        ctx.log(LogLevel::Trace, "not shown");
+        ctx.frame("#L.");
+        assert!(!ctx.log_enabled(LogLevel::Trace));
+        assert_eq!(
+            *output.lock().unwrap(),
+            vec!["Info round 1", "Debug occupied 37"]
+        );
+
+        let sink = RecordingSink::default();
+        let output = sink.output.clone();
+        let ctx = Context::default().with_output(LogLevel::Error, true, Box::new(sink));
+        ctx.info("not shown");
+        ctx.frame("#L.");
+        assert_eq!(*output.lock().unwrap(), vec!["frame #L."]);
+    }
+
+    #[test]
+    fn cancels_each_worker_on_its_own() {
+        let ctx = Context::default();
+        let worker = ctx.for_worker();
+        assert!(ctx.check_cancelled().is_ok());
+        worker.cancel();
+        assert!(matches!(
+            worker.check_cancelled(),
+            Err(SolveError::Cancelled)
+        ));
+        assert!(ctx.check_cancelled().is_ok());
+    }
+}
@@ -1,44 +1,51 @@
 use std::collections::{HashMap, HashSet};
 
-use crate::ProblemSolution;
+use crate::context::Context;
+use crate::problem::{ProblemSolution, SolveError, SolveResult};
 #[allow(unused_imports)]
 use aoc_parse::{parser, prelude::*};
 pub struct Solution {}
 
 impl ProblemSolution for Solution {
-    fn solve_a(&self, input: &str) -> Option<String> {
+    fn solve_a(&self, input: &str, ctx: &Context) -> SolveResult {
+        let target: u64 = ctx.param("target")?;
         let p = parser!(lines(u64));
-        let entries = p.parse(input).unwrap();
+        let entries = p.parse(input)?;
         let entries_set: HashSet<_> = entries.iter().cloned().collect();
-        let mut answer = 0;
         for entry in entries {
-            if entries_set.contains(&(2020 - entry)) {
-                answer = entry * (2020 - entry);
-                break;
+            let Some(other) = target.checked_sub(entry) else {
+                continue;
+            };
+            if entries_set.contains(&other) {
+                let answer = entry * other;
+                return Ok(answer.into());
             }
         }
-        Some(answer.to_string())
+        Err(SolveError::NoAnswer)
     }
 
-    fn solve_b(&self, input: &str) -> Option<String> {
+    fn solve_b(&self, input: &str, ctx: &Context) -> SolveResult {
+        let target: u64 = ctx.param("target")?;
         let p = parser!(lines(u64));
-        let entries = p.parse(input).unwrap();
+        let entries = p.parse(input)?;
         let mut pair_map = HashMap::new();
         for e1 in entries.iter() {
             for e2 in entries.iter() {
                 pair_map.insert(e1 + e2, (e1, e2));
             }
         }
-        let mut answer = 0;
         for e3 in entries.iter() {
-            match pair_map.get(&(2020 - e3)) {
+            let Some(rest) = target.checked_sub(*e3) else {
+                continue;
+            };
+            match pair_map.get(&rest) {
                 Some((e1, e2)) => {
-                    answer = *e1 * *e2 * e3;
-                    break;
+                    let answer = *e1 * *e2 * e3;
+                    return Ok(answer.into());
                 }
                 None => continue,
             }
         }
-        Some(answer.to_string())
+        Err(SolveError::NoAnswer)
     }
 }
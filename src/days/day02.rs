@@ -1,6 +1,7 @@
-use crate::ProblemSolution;
+use crate::context::Context;
+use crate::problem::{ProblemSolution, SolveResult};
 #[allow(unused_imports)]
-use aoc_parse::{parser, prelude::*};
+use aoc_parse::{parser, prelude::*, ParseError};
 pub struct Solution {}
 
 struct PasswordLine {
@@ -18,45 +19,47 @@ impl PasswordLine {
                 count += 1;
             }
         }
-        if count < self.min {
-            false
-        } else if count > self.max {
-            false
-        } else {
-            true
-        }
+        (self.min..=self.max).contains(&count)
+    }
+
+    // Positions count from 1, and one outside the password doesn't hold the character
+    fn has_character_at(&self, position: usize) -> bool {
+        position
+            .checked_sub(1)
+            .and_then(|idx| self.password.get(idx))
+            .is_some_and(|c| *c == self.character)
     }
 
     fn is_valid_b(&self) -> bool {
         let mut matches = 0;
-        if self.password[self.min - 1] == self.character {
+        if self.has_character_at(self.min) {
             matches += 1;
         }
-        if self.password[self.max - 1] == self.character {
+        if self.has_character_at(self.max) {
             matches += 1;
         }
         matches == 1
     }
 }
 
-fn get_lines(input: &str) -> impl Iterator<Item = PasswordLine> {
+fn get_lines(input: &str) -> Result<impl Iterator<Item = PasswordLine>, ParseError> {
     let p = parser!(lines(
         min:usize "-" max:usize " " character:lower ": " password:lower+
         => PasswordLine { min , max, character, password }
     ));
-    p.parse(input).unwrap().into_iter()
+    Ok(p.parse(input)?.into_iter())
 }
 
 impl ProblemSolution for Solution {
-    fn solve_a(&self, input: &str) -> Option<String> {
-        let password_lines = get_lines(&input);
+    fn solve_a(&self, input: &str, _ctx: &Context) -> SolveResult {
+        let password_lines = get_lines(input)?;
         let n_valid = password_lines.filter(|line| line.is_valid_a()).count();
-        Some(n_valid.to_string())
+        Ok(n_valid.into())
     }
 
-    fn solve_b(&self, input: &str) -> Option<String> {
-        let password_lines = get_lines(&input);
+    fn solve_b(&self, input: &str, _ctx: &Context) -> SolveResult {
+        let password_lines = get_lines(input)?;
         let n_valid = password_lines.filter(|line| line.is_valid_b()).count();
-        Some(n_valid.to_string())
+        Ok(n_valid.into())
     }
 }
@@ -1,4 +1,5 @@
-use crate::ProblemSolution;
+use crate::context::Context;
+use crate::problem::{ProblemSolution, SolveError, SolveResult};
 #[allow(unused_imports)]
 use aoc_parse::{parser, prelude::*};
 pub struct Solution {}
@@ -18,7 +19,22 @@ impl From<char> for Space {
     }
 }
 
-fn run_slope_experiment(lines: &Vec<Vec<Space>>, slope: (usize, usize)) -> usize {
+// The map, which must have rows of the same width to wrap around
+fn parse_input(input: &str) -> Result<Vec<Vec<Space>>, SolveError> {
+    let p = parser!(lines({"." => Space::Empty, "#" => Space::Tree}+));
+    let lines = p.parse(input)?;
+    let Some(width) = lines.first().map(Vec::len) else {
+        return Err(SolveError::UnsupportedInput("the map is empty".to_string()));
+    };
+    if lines.iter().any(|line| line.len() != width) {
+        return Err(SolveError::UnsupportedInput(
+            "the map's rows have different widths".to_string(),
+        ));
+    }
+    Ok(lines)
+}
+
+fn run_slope_experiment(lines: &[Vec<Space>], slope: (usize, usize)) -> usize {
     let height = lines.len();
     let width = lines[0].len();
     let mut x: usize = 0;
@@ -36,16 +52,14 @@ fn run_slope_experiment(lines: &Vec<Vec<Space>>, slope: (usize, usize)) -> usize
 }
 
 impl ProblemSolution for Solution {
-    fn solve_a(&self, input: &str) -> Option<String> {
-        let p = parser!(lines({"." => Space::Empty, "#" => Space::Tree}+));
-        let lines = p.parse(input).unwrap();
+    fn solve_a(&self, input: &str, _ctx: &Context) -> SolveResult {
+        let lines = parse_input(input)?;
         let trees = run_slope_experiment(&lines, (3, 1));
-        Some(trees.to_string())
+        Ok(trees.into())
     }
 
-    fn solve_b(&self, input: &str) -> Option<String> {
-        let p = parser!(lines({"." => Space::Empty, "#" => Space::Tree}+));
-        let lines = p.parse(input).unwrap();
+    fn solve_b(&self, input: &str, _ctx: &Context) -> SolveResult {
+        let lines = parse_input(input)?;
 
         let mut answer = 1;
         answer *= run_slope_experiment(&lines, (1, 1));
@@ -54,6 +68,6 @@ impl ProblemSolution for Solution {
         answer *= run_slope_experiment(&lines, (7, 1));
         answer *= run_slope_experiment(&lines, (1, 2));
 
-        Some(answer.to_string())
+        Ok(answer.into())
     }
 }
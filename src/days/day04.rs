@@ -1,11 +1,12 @@
 use std::collections::HashMap;
 
-use crate::ProblemSolution;
+use crate::context::Context;
+use crate::problem::{ParsedSolution, SolveError, SolveResult};
 #[allow(unused_imports)]
-use aoc_parse::{parser, prelude::*};
+use aoc_parse::{parser, prelude::*, ParseError};
 pub struct Solution {}
 
-struct Passport(HashMap<String, String>);
+pub struct Passport(HashMap<String, String>);
 
 enum Units {
     Cm,
@@ -24,8 +25,10 @@ impl Passport {
         if byr.len() != 4 {
             return false;
         }
-        let byr: usize = byr.parse().unwrap();
-        if byr < 1920 || byr > 2002 {
+        let Ok(byr) = byr.parse::<usize>() else {
+            return false;
+        };
+        if !(1920..=2002).contains(&byr) {
             return false;
         }
 
@@ -33,8 +36,10 @@ impl Passport {
         if iyr.len() != 4 {
             return false;
         }
-        let iyr: usize = iyr.parse().unwrap();
-        if iyr < 2010 || iyr > 2020 {
+        let Ok(iyr) = iyr.parse::<usize>() else {
+            return false;
+        };
+        if !(2010..=2020).contains(&iyr) {
             return false;
         }
 
@@ -42,22 +47,24 @@ impl Passport {
         if eyr.len() != 4 {
             return false;
         }
-        let eyr: usize = eyr.parse().unwrap();
-        if eyr < 2020 || eyr > 2030 {
+        let Ok(eyr) = eyr.parse::<usize>() else {
+            return false;
+        };
+        if !(2020..=2030).contains(&eyr) {
             return false;
         }
 
         let hgt_parser = parser!(usize {"cm" => Units::Cm, "in" => Units::In});
         let hgt = self.0.get("hgt").unwrap();
-        match hgt_parser.parse(&hgt) {
+        match hgt_parser.parse(hgt) {
             Err(_) => return false,
             Ok((height, Units::Cm)) => {
-                if height < 150 || height > 193 {
+                if !(150..=193).contains(&height) {
                     return false;
                 }
             }
             Ok((height, Units::In)) => {
-                if height < 59 || height > 76 {
+                if !(59..=76).contains(&height) {
                     return false;
                 }
             }
@@ -73,19 +80,19 @@ impl Passport {
            hcl_char_parser
         );
         let hcl = self.0.get("hcl").unwrap();
-        if hcl_parser.parse(&hcl).is_err() {
+        if hcl_parser.parse(hcl).is_err() {
             return false;
         }
 
         let ecl_parser = parser!({"amb", "blu", "brn", "gry", "grn", "hzl", "oth"});
         let ecl = self.0.get("ecl").unwrap();
-        if ecl_parser.parse(&ecl).is_err() {
+        if ecl_parser.parse(ecl).is_err() {
             return false;
         }
 
         let pid = self.0.get("pid").unwrap();
         let pid_parser = parser!(digit+);
-        if pid_parser.parse(&pid).is_err() {
+        if pid_parser.parse(pid).is_err() {
             return false;
         }
         if pid.len() != 9 {
@@ -96,7 +103,7 @@ impl Passport {
     }
 }
 
-fn parse_passports(input: &str) -> impl Iterator<Item = Passport> {
+fn parse_passports(input: &str) -> Result<Vec<Passport>, ParseError> {
     let key_or_value_parser = parser!(string({alnum, "#" => '#'}+));
     let entry_parser = parser!(key_or_value_parser ":" key_or_value_parser);
     let passport_parser = parser!(
@@ -105,22 +112,27 @@ fn parse_passports(input: &str) -> impl Iterator<Item = Passport> {
         Passport(passport_lines.into_iter().flatten().collect())
     );
     let p = parser!(sections(passport_parser));
-    p.parse(input).unwrap().into_iter()
+    p.parse(input)
 }
 
-impl ProblemSolution for Solution {
-    fn solve_a(&self, input: &str) -> Option<String> {
-        let passports = parse_passports(input);
-        let n_valid = passports.filter(|p| p.all_present()).count();
-        Some(n_valid.to_string())
+impl ParsedSolution for Solution {
+    type Parsed = Vec<Passport>;
+
+    fn parse(&self, input: &str, _ctx: &Context) -> Result<Self::Parsed, SolveError> {
+        Ok(parse_passports(input)?)
     }
 
-    fn solve_b(&self, input: &str) -> Option<String> {
-        let passports = parse_passports(input);
+    fn part_a(&self, passports: &Self::Parsed, _ctx: &Context) -> SolveResult {
+        let n_valid = passports.iter().filter(|p| p.all_present()).count();
+        Ok(n_valid.into())
+    }
+
+    fn part_b(&self, passports: &Self::Parsed, _ctx: &Context) -> SolveResult {
         let n_valid = passports
+            .iter()
             .filter(|p| p.all_present())
             .filter(|p| p.all_valid())
             .count();
-        Some(n_valid.to_string())
+        Ok(n_valid.into())
     }
 }
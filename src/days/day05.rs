@@ -1,6 +1,7 @@
-use crate::ProblemSolution;
+use crate::context::Context;
+use crate::problem::{ProblemSolution, SolveError, SolveResult};
 #[allow(unused_imports)]
-use aoc_parse::{parser, prelude::*};
+use aoc_parse::{parser, prelude::*, ParseError};
 pub struct Solution {}
 
 #[derive(Debug)]
@@ -17,16 +18,14 @@ impl Seat {
 
 fn binary_to_usize(binary: Vec<usize>) -> usize {
     let mut count = 0;
-    let mut power = 0;
     let base: usize = 2;
-    for next_digit in binary.into_iter().rev() {
-        count += next_digit * base.pow(power);
-        power += 1;
+    for (power, next_digit) in binary.into_iter().rev().enumerate() {
+        count += next_digit * base.pow(power as u32);
     }
     count
 }
 
-fn parse_input(input: &str) -> Vec<Seat> {
+fn parse_input(input: &str) -> Result<Vec<Seat>, ParseError> {
     // Give 0 to F, 1 to B
     let fb_parser = parser!(char_of("FB"));
     // Give 0 to L, 1 to R
@@ -36,28 +35,33 @@ fn parse_input(input: &str) -> Vec<Seat> {
     let column_parser = parser!(dgs:lr_parser+ => binary_to_usize(dgs));
     let seat_parser = parser!(row:row_parser column:column_parser => Seat{row, column});
 
-    parser!(lines(seat_parser)).parse(input).unwrap()
+    parser!(lines(seat_parser)).parse(input)
 }
 
 impl ProblemSolution for Solution {
-    fn solve_a(&self, input: &str) -> Option<String> {
-        let seats = parse_input(input);
-        let max_id = seats.into_iter().map(|s| s.id()).max().unwrap();
-        Some(max_id.to_string())
+    fn solve_a(&self, input: &str, _ctx: &Context) -> SolveResult {
+        let seats = parse_input(input)?;
+        let max_id = seats
+            .into_iter()
+            .map(|s| s.id())
+            .max()
+            .ok_or(SolveError::NoAnswer)?;
+        Ok(max_id.into())
     }
 
-    fn solve_b(&self, input: &str) -> Option<String> {
-        let seats = parse_input(input);
+    fn solve_b(&self, input: &str, _ctx: &Context) -> SolveResult {
+        let seats = parse_input(input)?;
         let mut all_ids: Vec<_> = seats.into_iter().map(|s| s.id()).collect();
         all_ids.sort_unstable();
-        let mut previous_id = all_ids[0] - 1;
-        for next_id in all_ids {
+        let (&first_id, other_ids) = all_ids.split_first().ok_or(SolveError::NoAnswer)?;
+        let mut previous_id = first_id;
+        for &next_id in other_ids {
             if next_id != previous_id + 1 {
                 let my_id = previous_id + 1;
-                return Some(my_id.to_string());
+                return Ok(my_id.into());
             }
             previous_id = next_id;
         }
-        unreachable!()
+        Err(SolveError::NoAnswer)
     }
 }
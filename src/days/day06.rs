@@ -1,8 +1,9 @@
 use std::collections::HashSet;
 
-use crate::ProblemSolution;
+use crate::context::Context;
+use crate::problem::{ProblemSolution, SolveResult};
 #[allow(unused_imports)]
-use aoc_parse::{parser, prelude::*};
+use aoc_parse::{parser, prelude::*, ParseError};
 pub struct Solution {}
 
 struct PersonAnswers(Vec<char>);
@@ -10,12 +11,7 @@ struct GroupAnswers(Vec<PersonAnswers>);
 
 impl GroupAnswers {
     fn union_size(&self) -> usize {
-        let union: HashSet<_> = self
-            .0
-            .iter()
-            .map(|person| person.0.iter())
-            .flatten()
-            .collect();
+        let union: HashSet<_> = self.0.iter().flat_map(|person| person.0.iter()).collect();
         union.len()
     }
 
@@ -32,23 +28,23 @@ impl GroupAnswers {
     }
 }
 
-fn parse_input(input: &str) -> Vec<GroupAnswers> {
+fn parse_input(input: &str) -> Result<Vec<GroupAnswers>, ParseError> {
     let person_parser = parser!(yeses:lower+ => PersonAnswers(yeses));
     let group_parser = parser!(people:lines(person_parser) => GroupAnswers(people));
     let p = parser!(sections(group_parser));
-    p.parse(input).unwrap()
+    p.parse(input)
 }
 
 impl ProblemSolution for Solution {
-    fn solve_a(&self, input: &str) -> Option<String> {
-        let groups = parse_input(input);
+    fn solve_a(&self, input: &str, _ctx: &Context) -> SolveResult {
+        let groups = parse_input(input)?;
         let total: usize = groups.into_iter().map(|grp| grp.union_size()).sum();
-        Some(total.to_string())
+        Ok(total.into())
     }
 
-    fn solve_b(&self, input: &str) -> Option<String> {
-        let groups = parse_input(input);
+    fn solve_b(&self, input: &str, _ctx: &Context) -> SolveResult {
+        let groups = parse_input(input)?;
         let total: usize = groups.into_iter().map(|grp| grp.intersection_size()).sum();
-        Some(total.to_string())
+        Ok(total.into())
     }
 }
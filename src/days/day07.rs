@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
-use crate::ProblemSolution;
+use crate::context::Context;
+use crate::problem::{ProblemSolution, SolveError, SolveResult};
 #[allow(unused_imports)]
 use aoc_parse::{parser, prelude::*};
 pub struct Solution {}
@@ -10,7 +11,20 @@ struct BagType(String, String);
 
 struct BagTree(HashMap<BagType, Vec<(usize, BagType)>>);
 
-fn parse_input(input: &str) -> BagTree {
+impl BagTree {
+    fn contents(&self, bag_type: &BagType) -> Result<&[(usize, BagType)], SolveError> {
+        self.0.get(bag_type).map(Vec::as_slice).ok_or_else(|| {
+            SolveError::UnsupportedInput(format!(
+                "no rule for {} {} bags",
+                bag_type.0, bag_type.1
+            ))
+        })
+    }
+}
+
+// Checks that every bag that appears inside another has a rule of its own,
+// so that the searches below can look up any bag they reach
+fn parse_input(input: &str) -> Result<BagTree, SolveError> {
     let bag_type = parser!(
         word1:string(lower+) " " word2:string(lower+) => BagType(word1, word2)
     );
@@ -30,7 +44,13 @@ fn parse_input(input: &str) -> BagTree {
     );
 
     let p = parser!(hash_map(lines(line_parser)));
-    BagTree(p.parse(input).unwrap())
+    let bag_tree = BagTree(p.parse(input)?);
+    for contents in bag_tree.0.values() {
+        for (_count, bag_type) in contents {
+            bag_tree.contents(bag_type)?;
+        }
+    }
+    Ok(bag_tree)
 }
 
 fn contains_shiny_gold(
@@ -38,14 +58,15 @@ fn contains_shiny_gold(
     bag_tree: &BagTree,
     cache: &mut HashMap<BagType, bool>,
 ) -> bool {
-    if let Some(contains) = cache.get(&query_key) {
+    if let Some(contains) = cache.get(query_key) {
         // Already computed
         return *contains;
     }
 
     let shiny_gold = BagType("shiny".to_string(), "gold".to_string());
 
-    for (_count, bag_type) in bag_tree.0.get(&query_key).unwrap() {
+    // Every bag was checked to have a rule when parsing
+    for (_count, bag_type) in &bag_tree.0[query_key] {
         // If bag type is what we want then insert true
         if *bag_type == shiny_gold {
             cache.insert(query_key.clone(), true);
@@ -60,7 +81,7 @@ fn contains_shiny_gold(
 
     // No contents contain
     cache.insert(query_key.clone(), false);
-    return false;
+    false
 }
 
 fn bags_inside(
@@ -72,10 +93,7 @@ fn bags_inside(
         return *count;
     }
 
-    let contents = bag_tree
-        .0
-        .get(query_key)
-        .unwrap()
+    let contents = bag_tree.0[query_key]
         .iter()
         .map(|(count, sub_type)| bags_inside(sub_type, bag_tree, cache) * count + count)
         .sum();
@@ -86,8 +104,8 @@ fn bags_inside(
 }
 
 impl ProblemSolution for Solution {
-    fn solve_a(&self, input: &str) -> Option<String> {
-        let bag_tree: BagTree = parse_input(input).into();
+    fn solve_a(&self, input: &str, _ctx: &Context) -> SolveResult {
+        let bag_tree = parse_input(input)?;
         let all_types: Vec<_> = bag_tree.0.keys().cloned().collect();
         let mut cache: HashMap<BagType, bool> = HashMap::default();
 
@@ -96,14 +114,15 @@ impl ProblemSolution for Solution {
             .filter(|bag_type| contains_shiny_gold(bag_type, &bag_tree, &mut cache))
             .count();
 
-        Some(n_types_contain.to_string())
+        Ok(n_types_contain.into())
     }
 
-    fn solve_b(&self, input: &str) -> Option<String> {
-        let bag_tree: BagTree = parse_input(input).into();
+    fn solve_b(&self, input: &str, _ctx: &Context) -> SolveResult {
+        let bag_tree = parse_input(input)?;
         let shiny_gold = BagType("shiny".to_string(), "gold".to_string());
+        bag_tree.contents(&shiny_gold)?;
         let mut cache = HashMap::default();
         let contents = bags_inside(&shiny_gold, &bag_tree, &mut cache);
-        Some(contents.to_string())
+        Ok(contents.into())
     }
 }
@@ -1,8 +1,9 @@
 use std::collections::HashSet;
 
-use crate::ProblemSolution;
+use crate::context::Context;
+use crate::problem::{ProblemSolution, SolveError, SolveResult};
 #[allow(unused_imports)]
-use aoc_parse::{parser, prelude::*};
+use aoc_parse::{parser, prelude::*, ParseError};
 pub struct Solution {}
 
 #[derive(Clone, Copy)]
@@ -37,37 +38,45 @@ impl MachineState {
         }
     }
 
-    fn update(&self, instruction: Instruction) -> Self {
+    // None for a jump to before the first instruction
+    fn update(&self, instruction: Instruction) -> Option<Self> {
         use Instruction::*;
-        match instruction {
+        let next_state = match instruction {
             Acc(arg) => MachineState {
                 position: self.position + 1,
                 accumulator: self.accumulator + arg,
             },
             Jmp(arg) => MachineState {
-                position: (self.position as isize + arg) as usize,
+                position: self.position.checked_add_signed(arg)?,
                 accumulator: self.accumulator,
             },
             Nop(_) => MachineState {
                 position: self.position + 1,
                 accumulator: self.accumulator,
             },
-        }
+        };
+        Some(next_state)
     }
 }
 
 enum SimulationOutcome {
     InfiniteLoop(isize),
     Terminates(isize),
+    // Also what an empty program does
+    JumpsOutside,
 }
 use SimulationOutcome::*;
 
-fn simulate(instructions: &Vec<Instruction>) -> SimulationOutcome {
+fn simulate(instructions: &[Instruction]) -> SimulationOutcome {
     let mut state = MachineState::init();
     let mut prior_positions: HashSet<usize> = HashSet::default();
     loop {
-        let next_instruction = instructions[state.position];
-        let next_state = state.update(next_instruction);
+        let Some(next_state) = instructions
+            .get(state.position)
+            .and_then(|instruction| state.update(*instruction))
+        else {
+            return JumpsOutside;
+        };
         if prior_positions.contains(&next_state.position) {
             // Return accumulator before loop begins
             return InfiniteLoop(state.accumulator);
@@ -81,37 +90,41 @@ fn simulate(instructions: &Vec<Instruction>) -> SimulationOutcome {
     }
 }
 
-fn parse_input(input: &str) -> Vec<Instruction> {
+fn parse_input(input: &str) -> Result<Vec<Instruction>, ParseError> {
     let p = parser!(lines({
         "acc " arg:isize => Acc(arg),
         "jmp " arg:isize => Jmp(arg),
         "nop " arg:isize => Nop(arg)
     }));
-    p.parse(input).unwrap()
+    p.parse(input)
 }
 
 impl ProblemSolution for Solution {
-    fn solve_a(&self, input: &str) -> Option<String> {
-        let instructions = parse_input(input);
+    fn solve_a(&self, input: &str, _ctx: &Context) -> SolveResult {
+        let instructions = parse_input(input)?;
         match simulate(&instructions) {
-            InfiniteLoop(acc) => Some(acc.to_string()),
-            Terminates(_) => unreachable!(),
+            InfiniteLoop(acc) => Ok(acc.into()),
+            Terminates(_) => Err(SolveError::NoAnswer),
+            JumpsOutside => Err(SolveError::UnsupportedInput(
+                "the program jumps outside its instructions".to_string(),
+            )),
         }
     }
 
-    fn solve_b(&self, input: &str) -> Option<String> {
-        let mut instructions = parse_input(input);
+    fn solve_b(&self, input: &str, _ctx: &Context) -> SolveResult {
+        let mut instructions = parse_input(input)?;
         for idx in 0..instructions.len() {
             instructions[idx] = instructions[idx].swap();
             match simulate(&instructions) {
-                InfiniteLoop(_) => {
+                // Swapping can also make a jump leave the program
+                InfiniteLoop(_) | JumpsOutside => {
                     // Undo instruction swap before continuing
                     instructions[idx] = instructions[idx].swap();
                     continue;
                 }
-                Terminates(acc) => return Some(acc.to_string()),
+                Terminates(acc) => return Ok(acc.into()),
             }
         }
-        unreachable!()
+        Err(SolveError::NoAnswer)
     }
 }
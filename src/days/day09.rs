@@ -1,51 +1,55 @@
 use rayon::prelude::*;
 
-use crate::ProblemSolution;
+use crate::context::Context;
+use crate::problem::{ParsedSolution, SolveError, SolveResult};
 #[allow(unused_imports)]
-use aoc_parse::{parser, prelude::*};
+use aoc_parse::{parser, prelude::*, ParseError};
 use rayon::iter::IntoParallelIterator;
 pub struct Solution {}
 
-fn parse_input(input: &str) -> Vec<usize> {
-    parser!(lines(usize)).parse(input).unwrap()
+fn parse_input(input: &str) -> Result<Vec<usize>, ParseError> {
+    parser!(lines(usize)).parse(input)
 }
 
-fn populate_row(sum_grid: &mut Vec<Vec<usize>>, row: usize, numbers: &Vec<usize>) {
+fn populate_row(sum_grid: &mut Vec<Vec<usize>>, row: usize, numbers: &[usize]) {
     let new_row = (0..row).map(|col| numbers[row] + numbers[col]).collect();
     sum_grid.push(new_row);
 }
 
-fn find_failure(numbers: &Vec<usize>) -> usize {
+// The first number that isn't the sum of two of the `lookback` numbers before it
+fn find_failure(numbers: &[usize], lookback: usize) -> Option<usize> {
     let mut working_idx = 0;
-    let lookback = 25;
     let mut sum_grid = vec![];
     'outer: loop {
         working_idx += 1;
-        populate_row(&mut sum_grid, working_idx - 1, &numbers);
+        // Every number was a valid sum
+        if working_idx >= numbers.len() {
+            break 'outer None;
+        }
+        populate_row(&mut sum_grid, working_idx - 1, numbers);
         // Premable
         if working_idx < lookback {
             continue 'outer;
         }
         // Main loop
-        for i in (working_idx - lookback)..working_idx {
-            for j in (working_idx - lookback)..i {
-                if sum_grid[i][j] == numbers[working_idx] {
-                    continue 'outer;
-                }
+        let window_start = working_idx - lookback;
+        for row in &sum_grid[window_start..] {
+            if row[window_start..].contains(&numbers[working_idx]) {
+                continue 'outer;
             }
         }
-        break 'outer numbers[working_idx];
+        break 'outer Some(numbers[working_idx]);
     }
 }
 
 fn test_for_range_starting(
-    numbers: &Vec<usize>,
+    numbers: &[usize],
     start_idx: usize,
     target: usize,
 ) -> Option<(usize, usize)> {
     let mut sum = numbers[start_idx];
-    for end_idx in (start_idx + 1)..(numbers.len()) {
-        sum += numbers[end_idx];
+    for (end_idx, number) in numbers.iter().enumerate().skip(start_idx + 1) {
+        sum += number;
         if sum == target {
             return Some((start_idx, end_idx));
         }
@@ -53,28 +57,41 @@ fn test_for_range_starting(
             return None;
         }
     }
-    return None;
+    None
+}
+
+// Both parts need the failure number, so it is found once while parsing
+pub struct Parsed {
+    numbers: Vec<usize>,
+    failure: Option<usize>,
 }
 
-impl ProblemSolution for Solution {
-    fn solve_a(&self, input: &str) -> Option<String> {
-        let numbers = parse_input(input);
-        let failure_number = find_failure(&numbers);
-        Some(failure_number.to_string())
+impl ParsedSolution for Solution {
+    type Parsed = Parsed;
+
+    fn parse(&self, input: &str, ctx: &Context) -> Result<Self::Parsed, SolveError> {
+        let numbers = parse_input(input)?;
+        let failure = find_failure(&numbers, ctx.param("lookback")?);
+        Ok(Parsed { numbers, failure })
+    }
+
+    fn part_a(&self, parsed: &Self::Parsed, _ctx: &Context) -> SolveResult {
+        let failure_number = parsed.failure.ok_or(SolveError::NoAnswer)?;
+        Ok(failure_number.into())
     }
 
-    fn solve_b(&self, input: &str) -> Option<String> {
-        let numbers = parse_input(input);
-        let target = find_failure(&numbers);
+    fn part_b(&self, parsed: &Self::Parsed, _ctx: &Context) -> SolveResult {
+        let numbers = &parsed.numbers;
+        let target = parsed.failure.ok_or(SolveError::NoAnswer)?;
         let (start_idx, end_idx) = (0..numbers.len())
             .into_par_iter()
-            .find_map_any(|start_idx| test_for_range_starting(&numbers, start_idx, target))
-            .unwrap();
+            .find_map_any(|start_idx| test_for_range_starting(numbers, start_idx, target))
+            .ok_or(SolveError::NoAnswer)?;
 
         let min_in_range = numbers[start_idx..=end_idx].iter().min().unwrap();
         let max_in_range = numbers[start_idx..=end_idx].iter().max().unwrap();
 
         let answer = min_in_range + max_in_range;
-        Some(answer.to_string())
+        Ok(answer.into())
     }
 }
@@ -1,12 +1,23 @@
 use std::collections::HashMap;
 
-use crate::ProblemSolution;
+use crate::context::Context;
+use crate::problem::{ProblemSolution, SolveError, SolveResult};
 #[allow(unused_imports)]
-use aoc_parse::{parser, prelude::*};
+use aoc_parse::{parser, prelude::*, ParseError};
 pub struct Solution {}
 
-fn parse_input(input: &str) -> Vec<usize> {
-    parser!(lines(usize)).parse(input).unwrap()
+fn parse_input(input: &str) -> Result<Vec<usize>, ParseError> {
+    parser!(lines(usize)).parse(input)
+}
+
+// The outlet, the adapters in order, then the device, which is rated 3 above the highest
+fn adapter_chain(input: &str) -> Result<Vec<usize>, SolveError> {
+    let mut outputs = parse_input(input)?;
+    outputs.push(0);
+    outputs.sort_unstable();
+    let device = outputs.last().map_or(3, |highest| highest + 3);
+    outputs.push(device);
+    Ok(outputs)
 }
 
 struct ChargerArray<'a> {
@@ -16,12 +27,15 @@ struct ChargerArray<'a> {
 }
 
 impl<'a> ChargerArray<'a> {
-    fn init(array: &'a Vec<usize>) -> Self {
-        ChargerArray {
-            source: *array.first().unwrap(),
+    fn init(array: &'a Vec<usize>) -> Result<Self, SolveError> {
+        let (Some(&source), Some(&target)) = (array.first(), array.last()) else {
+            return Err(SolveError::UnsupportedInput("there are no adapters".to_string()));
+        };
+        Ok(ChargerArray {
+            source,
             array,
-            target: *array.last().unwrap(),
-        }
+            target,
+        })
     }
 
     fn key(&self) -> (usize, usize) {
@@ -57,11 +71,8 @@ impl<'a> ChargerArray<'a> {
 }
 
 impl ProblemSolution for Solution {
-    fn solve_a(&self, input: &str) -> Option<String> {
-        let mut outputs = parse_input(input);
-        outputs.push(0);
-        outputs.sort_unstable();
-        outputs.push(outputs.last().unwrap() + 3);
+    fn solve_a(&self, input: &str, _ctx: &Context) -> SolveResult {
+        let outputs = adapter_chain(input)?;
 
         let differences = outputs.windows(2).map(|pair| pair[1] - pair[0]);
 
@@ -73,18 +84,15 @@ impl ProblemSolution for Solution {
 
         let answer = (diff_1s, diff_3s, diff_1s * diff_3s);
 
-        Some(answer.2.to_string())
+        Ok(answer.2.into())
     }
 
-    fn solve_b(&self, input: &str) -> Option<String> {
-        let mut outputs = parse_input(input);
-        outputs.push(0);
-        outputs.sort_unstable();
-        outputs.push(outputs.last().unwrap() + 3);
+    fn solve_b(&self, input: &str, _ctx: &Context) -> SolveResult {
+        let outputs = adapter_chain(input)?;
 
-        let c_arr = ChargerArray::init(&outputs);
+        let c_arr = ChargerArray::init(&outputs)?;
         let mut cache = HashMap::default();
 
-        Some(c_arr.n_paths(&mut cache).to_string())
+        Ok(c_arr.n_paths(&mut cache).into())
     }
 }
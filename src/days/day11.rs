@@ -1,6 +1,7 @@
-use std::{env, fmt::Display, hash::Hash, thread::sleep, time::Duration};
+use std::{fmt::Display, hash::Hash};
 
-use crate::ProblemSolution;
+use crate::context::{Context, LogLevel};
+use crate::problem::{ProblemSolution, SolveError, SolveResult};
 #[allow(unused_imports)]
 use aoc_parse::{parser, prelude::*};
 use colored::Colorize;
@@ -42,7 +43,7 @@ impl Display for GridState {
                     }
                 )?
             }
-            writeln!(f, "")?
+            writeln!(f)?
         }
         Ok(())
     }
@@ -194,68 +195,65 @@ impl<'a> GridPosition<'a> {
     }
 }
 
-fn parse_input(input: &str) -> GridState {
+fn parse_input(input: &str) -> Result<GridState, SolveError> {
     let pos_parser = parser!({
         "." => SeatState::Floor,
         "L" => SeatState::Empty
     });
     let grid_parser = parser!(lines(pos_parser+));
-    let grid = grid_parser.parse(input).unwrap();
-    let bounds = (grid.len(), grid[0].len());
-    GridState { grid, bounds }
-}
-
-fn check_should_print() -> bool {
-    env::var("PRINT_GRID").is_ok_and(|val| val == "true")
+    let grid = grid_parser.parse(input)?;
+    let Some(width) = grid.first().map(Vec::len) else {
+        return Err(SolveError::UnsupportedInput("the seating area is empty".to_string()));
+    };
+    if grid.iter().any(|row| row.len() != width) {
+        return Err(SolveError::UnsupportedInput(
+            "the seating area's rows have different widths".to_string(),
+        ));
+    }
+    let bounds = (grid.len(), width);
+    Ok(GridState { grid, bounds })
 }
 
 impl ProblemSolution for Solution {
-    fn solve_a(&self, input: &str) -> Option<String> {
-        let should_print = check_should_print();
-        let mut previous_grid = parse_input(input);
+    fn solve_a(&self, input: &str, ctx: &Context) -> SolveResult {
+        let mut previous_grid = parse_input(input)?;
         let mut next_grid = previous_grid.clone();
 
-        loop {
-            if should_print {
-                println!("{}[2J{previous_grid}", 27 as char);
-            }
+        for round in 1.. {
+            ctx.check_cancelled()?;
+            ctx.frame(&previous_grid);
             previous_grid.update_into_grid(&mut next_grid);
-            if should_print {
-                sleep(Duration::from_millis(100));
+            // Counting the seats walks the whole grid, so only do it when it will be logged
+            if ctx.log_enabled(LogLevel::Debug) {
+                ctx.debug(format_args!("round {round}: {} occupied", next_grid.n_occupied()));
             }
             if next_grid == previous_grid {
+                ctx.info(format_args!("stable after {round} rounds"));
                 break;
             }
             // Vec already has indirection so should be cheap
             std::mem::swap(&mut previous_grid, &mut next_grid);
         }
-        if should_print {
-            sleep(Duration::from_secs(5));
-        }
-        Some(next_grid.n_occupied().to_string())
+        Ok(next_grid.n_occupied().into())
     }
 
-    fn solve_b(&self, input: &str) -> Option<String> {
-        let should_print = check_should_print();
-        let mut previous_grid = parse_input(input);
+    fn solve_b(&self, input: &str, ctx: &Context) -> SolveResult {
+        let mut previous_grid = parse_input(input)?;
         let mut next_grid = previous_grid.clone();
-        loop {
-            if should_print {
-                println!("{}[2J{previous_grid}", 27 as char);
-            }
+        for round in 1.. {
+            ctx.check_cancelled()?;
+            ctx.frame(&previous_grid);
             previous_grid.update_into_grid_b(&mut next_grid);
-            if should_print {
-                sleep(Duration::from_millis(100));
+            if ctx.log_enabled(LogLevel::Debug) {
+                ctx.debug(format_args!("round {round}: {} occupied", next_grid.n_occupied()));
             }
             if next_grid == previous_grid {
+                ctx.info(format_args!("stable after {round} rounds"));
                 break;
             }
             // Vec already has indirection so should be cheap
             std::mem::swap(&mut previous_grid, &mut next_grid);
         }
-        if should_print {
-            sleep(Duration::from_secs(5));
-        }
-        Some(next_grid.n_occupied().to_string())
+        Ok(next_grid.n_occupied().into())
     }
 }
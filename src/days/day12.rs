@@ -1,6 +1,7 @@
-use crate::ProblemSolution;
+use crate::context::Context;
+use crate::problem::{ProblemSolution, SolveResult};
 #[allow(unused_imports)]
-use aoc_parse::{parser, prelude::*};
+use aoc_parse::{parser, prelude::*, ParseError};
 pub struct Solution {}
 
 #[derive(Debug, Clone, Copy)]
@@ -100,7 +101,7 @@ impl ShipStateWithWaypoint {
         match instruction {
             Left(count) => {
                 if count == 0 {
-                    return ();
+                    return;
                 }
                 self.waypoint = (-self.waypoint.1, self.waypoint.0);
                 self.update(Left(count - 1))
@@ -112,7 +113,7 @@ impl ShipStateWithWaypoint {
             West(count) => self.waypoint.0 -= count as isize,
             Forward(count) => {
                 if count == 0 {
-                    return ();
+                    return;
                 }
                 self.position.0 += self.waypoint.0;
                 self.position.1 += self.waypoint.1;
@@ -126,7 +127,7 @@ impl ShipStateWithWaypoint {
     }
 }
 
-fn parse_input(input: &str) -> Vec<Instruction> {
+fn parse_input(input: &str) -> Result<Vec<Instruction>, ParseError> {
     use Instruction::*;
     let p = parser!(lines({
         "L" num:usize => Left(num/90),
@@ -138,25 +139,25 @@ fn parse_input(input: &str) -> Vec<Instruction> {
         "W" num:usize => West(num),
     }));
 
-    p.parse(input).unwrap()
+    p.parse(input)
 }
 
 impl ProblemSolution for Solution {
-    fn solve_a(&self, input: &str) -> Option<String> {
-        let instructions = parse_input(input);
+    fn solve_a(&self, input: &str, _ctx: &Context) -> SolveResult {
+        let instructions = parse_input(input)?;
         let mut ship = ShipState::init();
         for instruction in instructions {
             ship.update(instruction);
         }
-        Some(ship.manhattan_norm().to_string())
+        Ok(ship.manhattan_norm().into())
     }
 
-    fn solve_b(&self, input: &str) -> Option<String> {
-        let instructions = parse_input(input);
+    fn solve_b(&self, input: &str, _ctx: &Context) -> SolveResult {
+        let instructions = parse_input(input)?;
         let mut ship = ShipStateWithWaypoint::init();
         for instruction in instructions {
             ship.update(instruction);
         }
-        Some(ship.manhattan_norm().to_string())
+        Ok(ship.manhattan_norm().into())
     }
 }
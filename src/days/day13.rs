@@ -1,6 +1,7 @@
-use crate::ProblemSolution;
+use crate::context::Context;
+use crate::problem::{ProblemSolution, SolveError, SolveResult};
 #[allow(unused_imports)]
-use aoc_parse::{parser, prelude::*};
+use aoc_parse::{parser, prelude::*, ParseError};
 pub struct Solution {}
 
 enum Service {
@@ -22,7 +23,9 @@ fn crt_iso_base_case(x1: ModuloNumber, x2: ModuloNumber) -> ModuloNumber {
 // Implements the isomorphism from the Chinese Remainder Theorem
 fn crt_iso(mut inputs: Vec<ModuloNumber>) -> ModuloNumber {
     let x1 = inputs.pop().unwrap();
-    let x2 = inputs.pop().unwrap();
+    let Some(x2) = inputs.pop() else {
+        return x1;
+    };
     let x12 = crt_iso_base_case(x1, x2);
     if inputs.is_empty() {
         x12
@@ -45,7 +48,7 @@ impl ModuloNumber {
     }
 }
 
-fn parse_input(input: &str) -> (usize, Vec<Service>) {
+fn parse_input(input: &str) -> Result<(usize, Vec<Service>), ParseError> {
     let p = parser!(
         line(usize)
         line(repeat_sep({
@@ -53,12 +56,12 @@ fn parse_input(input: &str) -> (usize, Vec<Service>) {
             freq:usize => Service::Frequency(freq)
         }, ","))
     );
-    p.parse(input).unwrap()
+    p.parse(input)
 }
 
 impl ProblemSolution for Solution {
-    fn solve_a(&self, input: &str) -> Option<String> {
-        let (arrival, services) = parse_input(input);
+    fn solve_a(&self, input: &str, _ctx: &Context) -> SolveResult {
+        let (arrival, services) = parse_input(input)?;
         let frequencies = services.iter().filter_map(|s| match s {
             Service::OutOfService => None,
             Service::Frequency(freq) => Some(freq),
@@ -66,14 +69,14 @@ impl ProblemSolution for Solution {
         let answer = frequencies
             .map(|freq| (freq, (*freq - arrival.rem_euclid(*freq))))
             .min_by_key(|(_freq, wait)| *wait)
-            .unwrap();
+            .ok_or_else(|| SolveError::UnsupportedInput("no buses in service".to_string()))?;
 
         let answer = answer.0 * answer.1;
-        Some(answer.to_string())
+        Ok(answer.into())
     }
 
-    fn solve_b(&self, input: &str) -> Option<String> {
-        let (_arrival, services) = parse_input(input);
+    fn solve_b(&self, input: &str, _ctx: &Context) -> SolveResult {
+        let (_arrival, services) = parse_input(input)?;
         let inputs: Vec<_> = services
             .iter()
             .enumerate()
@@ -87,8 +90,13 @@ impl ProblemSolution for Solution {
                 }
             })
             .collect();
+        if inputs.is_empty() {
+            return Err(SolveError::UnsupportedInput(
+                "no buses in service".to_string(),
+            ));
+        }
 
         let answer = crt_iso(inputs).number;
-        Some(answer.to_string())
+        Ok(answer.into())
     }
 }
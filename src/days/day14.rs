@@ -1,8 +1,9 @@
 use std::collections::{HashMap, HashSet};
 
-use crate::ProblemSolution;
+use crate::context::Context;
+use crate::problem::{ProblemSolution, SolveError, SolveResult};
 #[allow(unused_imports)]
-use aoc_parse::{parser, prelude::*};
+use aoc_parse::{parser, prelude::*, ParseError};
 pub struct Solution {}
 
 type MaskValue = Option<bool>;
@@ -39,20 +40,22 @@ fn apply_mask_to_address(address: BinaryNumber, mask: &Mask) -> Mask {
         .collect()
 }
 
-fn mask_to_all_addresses(mask: &mut Mask) -> HashSet<u64> {
+// There are 2^k addresses for k floating bits, so this checks for cancellation as it goes
+fn mask_to_all_addresses(mask: &mut Mask, ctx: &Context) -> Result<HashSet<u64>, SolveError> {
+    ctx.check_cancelled()?;
     let first_unknown_idx = mask.iter().position(|itm| itm.is_none());
     if let Some(idx) = first_unknown_idx {
         mask[idx] = Some(true);
-        let with_true_adds = mask_to_all_addresses(mask);
+        let with_true_adds = mask_to_all_addresses(mask, ctx)?;
         mask[idx] = Some(false);
-        let with_false_adds = mask_to_all_addresses(mask);
+        let with_false_adds = mask_to_all_addresses(mask, ctx)?;
         // Restore mask before propogating back up
         mask[idx] = None;
-        with_true_adds.union(&with_false_adds).cloned().collect()
+        Ok(with_true_adds.union(&with_false_adds).cloned().collect())
     } else {
         let mut answer = HashSet::new();
-        answer.insert(mask_to_integer(&mask));
-        answer
+        answer.insert(mask_to_integer(mask));
+        Ok(answer)
     }
 }
 
@@ -99,7 +102,7 @@ fn integer_to_binary(mut input: u64) -> BinaryNumber {
     output.to_vec()
 }
 
-fn parse_input(input: &str) -> Vec<Instruction> {
+fn parse_input(input: &str) -> Result<Vec<Instruction>, ParseError> {
     let mask_parser = parser!({
         "X" => None,
         "0" => Some(false),
@@ -113,15 +116,16 @@ fn parse_input(input: &str) -> Vec<Instruction> {
     });
 
     let p = parser!(lines(line_parser));
-    p.parse(input).unwrap()
+    p.parse(input)
 }
 
 impl ProblemSolution for Solution {
-    fn solve_a(&self, input: &str) -> Option<String> {
-        let instructions = parse_input(input);
+    fn solve_a(&self, input: &str, ctx: &Context) -> SolveResult {
+        let instructions = parse_input(input)?;
         let mut memory = HashMap::new();
         let mut mask = vec![None; 36];
         for instruction in instructions {
+            ctx.check_cancelled()?;
             match instruction {
                 Instruction::SetMask(mask_vec) => mask = mask_vec,
                 Instruction::SetMemory(address, val) => {
@@ -133,28 +137,29 @@ impl ProblemSolution for Solution {
             }
         }
 
-        let answer: u64 = memory.values().into_iter().sum();
-        Some(answer.to_string())
+        let answer: u64 = memory.values().sum();
+        Ok(answer.into())
     }
 
-    fn solve_b(&self, input: &str) -> Option<String> {
-        let instructions = parse_input(input);
+    fn solve_b(&self, input: &str, ctx: &Context) -> SolveResult {
+        let instructions = parse_input(input)?;
         let mut memory = HashMap::new();
         let mut mask = vec![None; 36];
         for instruction in instructions {
+            ctx.check_cancelled()?;
             match instruction {
                 Instruction::SetMask(mask_vec) => mask = mask_vec,
                 Instruction::SetMemory(address, val) => {
                     let address_as_binary = integer_to_binary(address);
                     let mut address_set = apply_mask_to_address(address_as_binary, &mask);
-                    for address in mask_to_all_addresses(&mut address_set) {
+                    for address in mask_to_all_addresses(&mut address_set, ctx)? {
                         memory.insert(address, val);
                     }
                 }
             }
         }
 
-        let answer: u64 = memory.values().into_iter().sum();
-        Some(answer.to_string())
+        let answer: u64 = memory.values().sum();
+        Ok(answer.into())
     }
 }
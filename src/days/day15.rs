@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
-use crate::ProblemSolution;
+use crate::context::Context;
+use crate::problem::{ProblemSolution, SolveError, SolveResult};
 #[allow(unused_imports)]
 use aoc_parse::{parser, prelude::*};
 pub struct Solution {}
@@ -41,24 +42,48 @@ impl MemoryGame {
         self.step_number += 1;
     }
 
-    fn get_nth_number(&mut self, n: usize) -> usize {
+    fn get_nth_number(&mut self, n: usize, ctx: &Context) -> Result<usize, SolveError> {
         while self.step_number != (n - 1) {
+            ctx.check_cancelled()?;
             self.advance();
         }
-        self.current_number
+        Ok(self.current_number)
     }
 }
 
+fn parse_input(input: &str) -> Result<Vec<usize>, SolveError> {
+    let numbers = parser!(line(repeat_sep(usize, ","))).parse(input)?;
+    if numbers.is_empty() {
+        return Err(SolveError::UnsupportedInput(
+            "no starting numbers".to_string(),
+        ));
+    }
+    Ok(numbers)
+}
+
+// The number spoken on the given turn, counting from 1. The game only runs past the
+// starting numbers, since it can't go back to an earlier turn.
+fn play(numbers: Vec<usize>, turns: usize, ctx: &Context) -> SolveResult {
+    if turns == 0 {
+        return Err(SolveError::UnsupportedInput(
+            "the game needs at least one turn".to_string(),
+        ));
+    }
+    if let Some(number) = numbers.get(turns - 1) {
+        return Ok((*number).into());
+    }
+    let mut game = MemoryGame::init(numbers);
+    Ok(game.get_nth_number(turns, ctx)?.into())
+}
+
 impl ProblemSolution for Solution {
-    fn solve_a(&self, input: &str) -> Option<String> {
-        let numbers = parser!(line(repeat_sep(usize, ","))).parse(input).unwrap();
-        let mut game = MemoryGame::init(numbers);
-        Some(game.get_nth_number(2020).to_string())
+    fn solve_a(&self, input: &str, ctx: &Context) -> SolveResult {
+        let numbers = parse_input(input)?;
+        play(numbers, ctx.param("turns_a")?, ctx)
     }
 
-    fn solve_b(&self, input: &str) -> Option<String> {
-        let numbers = parser!(line(repeat_sep(usize, ","))).parse(input).unwrap();
-        let mut game = MemoryGame::init(numbers);
-        Some(game.get_nth_number(30000000).to_string())
+    fn solve_b(&self, input: &str, ctx: &Context) -> SolveResult {
+        let numbers = parse_input(input)?;
+        play(numbers, ctx.param("turns_b")?, ctx)
     }
 }
@@ -1,8 +1,9 @@
 use std::collections::HashSet;
 
-use crate::ProblemSolution;
+use crate::context::Context;
+use crate::problem::{ParsedSolution, SolveError, SolveResult};
 #[allow(unused_imports)]
-use aoc_parse::{parser, prelude::*};
+use aoc_parse::{parser, prelude::*, ParseError};
 pub struct Solution {}
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -15,7 +16,7 @@ impl ValidRange {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
-struct FieldRule {
+pub struct FieldRule {
     name: String,
     ranges: [ValidRange; 2],
 }
@@ -27,12 +28,12 @@ impl FieldRule {
 }
 
 #[derive(Clone, Debug)]
-struct Ticket(Vec<usize>);
+pub struct Ticket(Vec<usize>);
 
 impl Ticket {
     fn get_invalid_fields<'a>(
         &'a self,
-        rules: &'a Vec<FieldRule>,
+        rules: &'a [FieldRule],
     ) -> impl Iterator<Item = usize> + 'a {
         self.0
             .iter()
@@ -41,7 +42,7 @@ impl Ticket {
     }
 }
 
-fn parse_input(input: &str) -> (Vec<FieldRule>, Ticket, Vec<Ticket>) {
+fn parse_input(input: &str) -> Result<(Vec<FieldRule>, Ticket, Vec<Ticket>), ParseError> {
     let range_parser = parser!(min:usize "-" max:usize => ValidRange(min, max));
     let rule_parser = parser!(
         name:string(any_char+) ": " r1:range_parser " or " r2:range_parser
@@ -64,21 +65,31 @@ fn parse_input(input: &str) -> (Vec<FieldRule>, Ticket, Vec<Ticket>) {
         )
     );
 
-    p.parse(input).unwrap()
+    p.parse(input)
 }
 
 // Surely one of the fields must now be fixed
 // Remove this from any other possibilities and keep iterating
 // Eventually we arrive at the only valid permutation
-fn find_only_permutation(mut possibilities: Vec<HashSet<FieldRule>>) -> Vec<FieldRule> {
+fn find_only_permutation(
+    mut possibilities: Vec<HashSet<FieldRule>>,
+    ctx: &Context,
+) -> Result<Vec<FieldRule>, SolveError> {
     let mut fixed: HashSet<usize> = HashSet::new();
     while possibilities.iter().any(|s| s.len() > 1) {
+        ctx.check_cancelled()?;
         let newly_fixed: Vec<_> = possibilities
             .iter()
             .enumerate()
             .filter(|(idx, p_set)| (!fixed.contains(idx)) && p_set.len() == 1)
             .map(|(idx, p_set)| (idx, p_set.iter().next().cloned().unwrap()))
             .collect();
+        // Otherwise every later pass would be the same as this one
+        if newly_fixed.is_empty() {
+            return Err(SolveError::UnsupportedInput(
+                "the rules fit the tickets in more than one order".to_string(),
+            ));
+        }
         for (fixed_idx, fixed_field) in newly_fixed {
             fixed.insert(fixed_idx);
             for (other_idx, p_set) in possibilities.iter_mut().enumerate() {
@@ -92,12 +103,16 @@ fn find_only_permutation(mut possibilities: Vec<HashSet<FieldRule>>) -> Vec<Fiel
 
     possibilities
         .into_iter()
-        .map(|mut p_set| p_set.drain().next().unwrap())
+        .map(|mut p_set| {
+            p_set.drain().next().ok_or_else(|| {
+                SolveError::UnsupportedInput("no rule fits every value of a field".to_string())
+            })
+        })
         .collect()
 }
 
 fn build_possible_field_names<'a>(
-    rules: &Vec<FieldRule>,
+    rules: &[FieldRule],
     tickets: impl Iterator<Item = &'a Ticket>,
     n_fields: usize,
 ) -> Vec<HashSet<FieldRule>> {
@@ -114,27 +129,31 @@ fn build_possible_field_names<'a>(
     possibilities
 }
 
-impl ProblemSolution for Solution {
-    fn solve_a(&self, input: &str) -> Option<String> {
-        let (rules, _my_ticket, nearby_tickets) = parse_input(input);
+impl ParsedSolution for Solution {
+    type Parsed = (Vec<FieldRule>, Ticket, Vec<Ticket>);
+
+    fn parse(&self, input: &str, _ctx: &Context) -> Result<Self::Parsed, SolveError> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part_a(&self, (rules, _my_ticket, nearby_tickets): &Self::Parsed, _ctx: &Context) -> SolveResult {
         let error_rate: usize = nearby_tickets
             .iter()
-            .flat_map(|ticket| ticket.get_invalid_fields(&rules))
+            .flat_map(|ticket| ticket.get_invalid_fields(rules))
             .sum();
-        Some(error_rate.to_string())
+        Ok(error_rate.into())
     }
 
-    fn solve_b(&self, input: &str) -> Option<String> {
-        let (rules, my_ticket, nearby_tickets) = parse_input(input);
+    fn part_b(&self, (rules, my_ticket, nearby_tickets): &Self::Parsed, ctx: &Context) -> SolveResult {
         let n_fields = my_ticket.0.len();
         let valid_nearby = nearby_tickets
             .iter()
-            .filter(|ticket| ticket.get_invalid_fields(&rules).next().is_none());
+            .filter(|ticket| ticket.get_invalid_fields(rules).next().is_none());
 
         // Use rules to filter out which field name could be in each position
-        let possibilities = build_possible_field_names(&rules, valid_nearby, n_fields);
+        let possibilities = build_possible_field_names(rules, valid_nearby, n_fields);
         // Find the only possible combination of names based on the above
-        let field_names = find_only_permutation(possibilities);
+        let field_names = find_only_permutation(possibilities, ctx)?;
 
         let departure_idxs = field_names
             .iter()
@@ -145,6 +164,6 @@ impl ProblemSolution for Solution {
         let departure_values = departure_idxs.map(|idx| my_ticket.0[idx]);
         let answer: usize = departure_values.product();
 
-        Some(answer.to_string())
+        Ok(answer.into())
     }
 }
@@ -1,6 +1,7 @@
 use std::iter;
 
-use crate::ProblemSolution;
+use crate::context::Context;
+use crate::problem::{ProblemSolution, SolveError, SolveResult};
 #[allow(unused_imports)]
 use aoc_parse::{parser, prelude::*};
 pub struct Solution {}
@@ -62,7 +63,7 @@ impl<const N: usize> PocketDimension<N> {
     //       We only need to pad if there is some Active state on the outer shell
     // Wrap pocket dimension in a shell of Inactive
     fn pad(&mut self) {
-        let new_bounds = self.bounds.clone().map(|b| b + 2);
+        let new_bounds = self.bounds.map(|b| b + 2);
         let mut new_states: Vec<State> = vec![];
         let max_new_idx = new_bounds.iter().product();
         for new_idx in 0..max_new_idx {
@@ -100,7 +101,7 @@ impl<const N: usize> PocketDimension<N> {
         let mut next_states = vec![];
         for idx in 0..(self.states.len()) {
             let pos = self.idx_to_position(idx);
-            let pos_ref = self.at_pos(pos.clone());
+            let pos_ref = self.at_pos(pos);
             let current_state = pos_ref.state();
             let active_nbrs = pos_ref
                 .neighbours()
@@ -138,7 +139,7 @@ impl<'a, const N: usize> PocketDimensionPosition<'a, N> {
             .position
             .iter()
             .zip(delta.iter())
-            .map(|(x, y)| (*x as isize + y))
+            .map(|(x, y)| *x as isize + y)
             .collect::<Vec<isize>>()
             .try_into()
             .unwrap();
@@ -168,43 +169,51 @@ impl<'a, const N: usize> PocketDimensionPosition<'a, N> {
     }
 }
 
-fn parse_input<const N: usize>(input: &str) -> PocketDimension<N> {
+fn parse_input<const N: usize>(input: &str) -> Result<PocketDimension<N>, SolveError> {
     let p = parser!(lines({
         "#" => Active,
         "." => Inactive
     }+));
-    let slice = p.parse(input).unwrap();
+    let slice = p.parse(input)?;
+    let Some(width) = slice.first().map(Vec::len) else {
+        return Err(SolveError::UnsupportedInput("the slice is empty".to_string()));
+    };
+    if slice.iter().any(|row| row.len() != width) {
+        return Err(SolveError::UnsupportedInput(
+            "the slice's rows have different widths".to_string(),
+        ));
+    }
     let bounds: [usize; N] = (0..(N - 2))
         .map(|_| 1)
-        .chain(iter::once(slice[0].len()))
+        .chain(iter::once(width))
         .chain(iter::once(slice.len()))
         .collect::<Vec<usize>>()
         .try_into()
         .unwrap();
-    PocketDimension {
+    Ok(PocketDimension {
         states: slice.into_iter().flat_map(|row| row.into_iter()).collect(),
         bounds,
-    }
+    })
 }
 
 // N is the number of dimensions (3 for part a, 4 for part b)
-fn solve<const N: usize>(input: &str) -> usize {
-    let mut pocket_dimension = parse_input::<N>(input);
+fn solve<const N: usize>(input: &str, cycles: usize) -> Result<usize, SolveError> {
+    let mut pocket_dimension = parse_input::<N>(input)?;
 
-    for _ in 0..6 {
+    for _ in 0..cycles {
         pocket_dimension.pad();
         pocket_dimension = pocket_dimension.produce_update();
     }
 
-    pocket_dimension.n_active()
+    Ok(pocket_dimension.n_active())
 }
 
 impl ProblemSolution for Solution {
-    fn solve_a(&self, input: &str) -> Option<String> {
-        Some(solve::<3>(input).to_string())
+    fn solve_a(&self, input: &str, ctx: &Context) -> SolveResult {
+        Ok(solve::<3>(input, ctx.param("cycles")?)?.into())
     }
 
-    fn solve_b(&self, input: &str) -> Option<String> {
-        Some(solve::<4>(input).to_string())
+    fn solve_b(&self, input: &str, ctx: &Context) -> SolveResult {
+        Ok(solve::<4>(input, ctx.param("cycles")?)?.into())
     }
 }
@@ -1,6 +1,7 @@
-use crate::ProblemSolution;
+use crate::context::Context;
+use crate::problem::{ParsedSolution, SolveError, SolveResult};
 #[allow(unused_imports)]
-use aoc_parse::{parser, prelude::*};
+use aoc_parse::{parser, prelude::*, ParseError};
 pub struct Solution {}
 
 #[derive(Debug)]
@@ -10,8 +11,8 @@ enum Expression {
     Integer(usize),
 }
 
-#[derive(Debug)]
-enum RawSymbol {
+#[derive(Debug, Clone)]
+pub enum RawSymbol {
     Multiply,
     Add,
     Integer(usize),
@@ -47,7 +48,7 @@ fn bracket_additions(symbols: &mut Vec<RawSymbol>) {
     bracket_additions(symbols);
 }
 
-fn all_operations_are_additions(symbols: &Vec<RawSymbol>) -> bool {
+fn all_operations_are_additions(symbols: &[RawSymbol]) -> bool {
     symbols[1..]
         .iter()
         .step_by(2)
@@ -105,7 +106,7 @@ impl Expression {
     }
 }
 
-fn parse_input(input: &str) -> Vec<Vec<RawSymbol>> {
+fn parse_input(input: &str) -> Result<Vec<Vec<RawSymbol>>, ParseError> {
     let raw_expression_parser = parser!(
         rule raw_expression: RawSymbol = {
             "*" => RawSymbol::Multiply,
@@ -121,25 +122,30 @@ fn parse_input(input: &str) -> Vec<Vec<RawSymbol>> {
     );
 
     let p = parser!(lines(raw_expression_parser));
-    p.parse(input).unwrap()
+    p.parse(input)
 }
 
-impl ProblemSolution for Solution {
-    fn solve_a(&self, input: &str) -> Option<String> {
-        let raw_symbols = parse_input(input);
+impl ParsedSolution for Solution {
+    type Parsed = Vec<Vec<RawSymbol>>;
+
+    fn parse(&self, input: &str, _ctx: &Context) -> Result<Self::Parsed, SolveError> {
+        Ok(parse_input(input)?)
+    }
+
+    // Building an expression consumes its symbols, so each part works on a copy
+    fn part_a(&self, raw_symbols: &Self::Parsed, _ctx: &Context) -> SolveResult {
         let answer: usize = raw_symbols
-            .into_iter()
-            .map(|symbols| build_expression::<false>(symbols).evaluate())
+            .iter()
+            .map(|symbols| build_expression::<false>(symbols.clone()).evaluate())
             .sum();
-        Some(answer.to_string())
+        Ok(answer.into())
     }
 
-    fn solve_b(&self, input: &str) -> Option<String> {
-        let raw_symbols = parse_input(input);
+    fn part_b(&self, raw_symbols: &Self::Parsed, _ctx: &Context) -> SolveResult {
         let answer: usize = raw_symbols
-            .into_iter()
-            .map(|symbols| build_expression::<true>(symbols).evaluate())
+            .iter()
+            .map(|symbols| build_expression::<true>(symbols.clone()).evaluate())
             .sum();
-        Some(answer.to_string())
+        Ok(answer.into())
     }
 }
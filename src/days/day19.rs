@@ -1,25 +1,25 @@
 use std::collections::{HashMap, HashSet};
 
-use crate::ProblemSolution;
+use crate::context::Context;
+use crate::problem::{ParsedSolution, SolveError, SolveResult};
 #[allow(unused_imports)]
 use aoc_parse::{parser, prelude::*};
-use itertools::Itertools;
 pub struct Solution {}
 
-#[derive(Debug, PartialEq, Eq)]
-enum ABChar {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ABChar {
     A,
     B,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum RawRule {
     MatchChar(ABChar),
     MatchRuleSequences(Vec<Vec<usize>>),
 }
 
-#[derive(Debug)]
-struct RuleSet {
+#[derive(Debug, Clone)]
+pub struct RuleSet {
     rules: HashMap<usize, RawRule>,
 }
 
@@ -30,6 +30,30 @@ impl RuleSet {
             idx,
         }
     }
+
+    // Checks that rule 0 and every rule referred to exist, so that matching can look them up
+    fn check(&self) -> Result<(), SolveError> {
+        let undefined = |idx| SolveError::UnsupportedInput(format!("rule {idx} is not defined"));
+        if !self.rules.contains_key(&0) {
+            return Err(undefined(0));
+        }
+        for (idx, rule) in &self.rules {
+            let RawRule::MatchRuleSequences(sequences) = rule else {
+                continue;
+            };
+            for sequence in sequences {
+                if sequence.is_empty() {
+                    return Err(SolveError::UnsupportedInput(format!(
+                        "rule {idx} has an empty sequence"
+                    )));
+                }
+                if let Some(missing) = sequence.iter().find(|i| !self.rules.contains_key(i)) {
+                    return Err(undefined(*missing));
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 struct Rule<'a> {
@@ -41,17 +65,18 @@ impl<'a> Rule<'a> {
     // Each element of the returned set is a possible number of characters that could be matched
     // by the rule
     fn match_lengths(&self, word: &[ABChar]) -> HashSet<usize> {
-        if word.len() == 0 {
+        if word.is_empty() {
             return HashSet::default();
         }
-        let raw_rule = self.rule_set.rules.get(&self.idx).unwrap();
+        // Rule sets are checked before matching, so the rule exists
+        let raw_rule = &self.rule_set.rules[&self.idx];
         match raw_rule {
             RawRule::MatchChar(abchar) => {
                 // Base case
-                if word.len() >= 1 && word[0] == *abchar {
-                    return HashSet::from([1]);
+                if !word.is_empty() && word[0] == *abchar {
+                    HashSet::from([1])
                 } else {
-                    return HashSet::default();
+                    HashSet::default()
                 }
             }
             RawRule::MatchRuleSequences(rule_sequences) => rule_sequences
@@ -59,8 +84,8 @@ impl<'a> Rule<'a> {
                 .flat_map(|rule_sequence| {
                     // Each rule sequence is checked individually and then merged
                     let rule_sequence = RuleSequence {
-                        rule_set: &self.rule_set,
-                        sequence: &rule_sequence,
+                        rule_set: self.rule_set,
+                        sequence: rule_sequence,
                     };
                     rule_sequence.match_lengths(word).into_iter()
                 })
@@ -106,7 +131,7 @@ impl<'a> RuleSequence<'a> {
     }
 }
 
-fn parse_input(input: &str) -> (RuleSet, Vec<Vec<ABChar>>) {
+fn parse_input(input: &str) -> Result<(RuleSet, Vec<Vec<ABChar>>), SolveError> {
     let abchar = parser!({'a' => ABChar::A, 'b' => ABChar::B});
     let rule_parser = parser!(
         idx:usize ": " raw_rule:{
@@ -122,36 +147,47 @@ fn parse_input(input: &str) -> (RuleSet, Vec<Vec<ABChar>>) {
         section(lines(word_parser))
     );
 
-    let (rules, words) = p.parse(input).unwrap();
+    let (rules, words) = p.parse(input)?;
     let rule_set = RuleSet {
         rules: rules.into_iter().collect(),
     };
-    (rule_set, words)
+    rule_set.check()?;
+    Ok((rule_set, words))
 }
 
-impl ProblemSolution for Solution {
-    fn solve_a(&self, input: &str) -> Option<String> {
-        let (rule_set, words) = parse_input(input);
-        let rule_0 = rule_set.rule(0);
-        let words_matching_0 = words
-            .into_iter()
-            .filter(|word| rule_0.fully_matches(word))
-            .count();
-        Some(words_matching_0.to_string())
+fn count_matching_0(rule_set: &RuleSet, words: &[Vec<ABChar>]) -> usize {
+    let rule_0 = rule_set.rule(0);
+    words
+        .iter()
+        .filter(|word| rule_0.fully_matches(word))
+        .count()
+}
+
+impl ParsedSolution for Solution {
+    type Parsed = (RuleSet, Vec<Vec<ABChar>>);
+
+    fn parse(&self, input: &str, _ctx: &Context) -> Result<Self::Parsed, SolveError> {
+        parse_input(input)
     }
 
-    fn solve_b(&self, input: &str) -> Option<String> {
-        let new_lines = input.lines().map(|line| {
-            if line.starts_with("8:") {
-                "8: 42 | 42 8"
-            } else if line.starts_with("11:") {
-                "11: 42 31 | 42 11 31"
-            } else {
-                line
-            }
-        });
-        let new_input: String = Itertools::intersperse(new_lines, "\n").collect();
+    fn part_a(&self, (rule_set, words): &Self::Parsed, _ctx: &Context) -> SolveResult {
+        Ok(count_matching_0(rule_set, words).into())
+    }
 
-        self.solve_a(&new_input)
+    fn part_b(&self, (rule_set, words): &Self::Parsed, _ctx: &Context) -> SolveResult {
+        // Replace rules 8 and 11 with their looping versions
+        let mut rule_set = rule_set.clone();
+        let looping_rules = [
+            (8, vec![vec![42], vec![42, 8]]),
+            (11, vec![vec![42, 31], vec![42, 11, 31]]),
+        ];
+        for (idx, sequences) in looping_rules {
+            if let Some(rule) = rule_set.rules.get_mut(&idx) {
+                *rule = RawRule::MatchRuleSequences(sequences);
+            }
+        }
+        // The looping rules refer to rules 42 and 31
+        rule_set.check()?;
+        Ok(count_matching_0(&rule_set, words).into())
     }
 }
@@ -1,4 +1,4 @@
-use crate::ProblemSolution;
+use crate::problem::ProblemSolution;
 #[allow(unused_imports)]
 use aoc_parse::{parser, prelude::*};
 pub struct Solution {}
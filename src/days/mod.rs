@@ -1,59 +1,253 @@
 use crate::io::AocRunError;
-use crate::problem::ProblemSolution;
-
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day24;
-mod day25;
-
-pub fn get_solution(problem: usize) -> Result<Box<dyn ProblemSolution>, AocRunError> {
-    match problem {
-        01 => Ok(Box::new(day01::Solution {})),
-        02 => Ok(Box::new(day02::Solution {})),
-        03 => Ok(Box::new(day03::Solution {})),
-        04 => Ok(Box::new(day04::Solution {})),
-        05 => Ok(Box::new(day05::Solution {})),
-        06 => Ok(Box::new(day06::Solution {})),
-        07 => Ok(Box::new(day07::Solution {})),
-        08 => Ok(Box::new(day08::Solution {})),
-        09 => Ok(Box::new(day09::Solution {})),
-        10 => Ok(Box::new(day10::Solution {})),
-        11 => Ok(Box::new(day11::Solution {})),
-        12 => Ok(Box::new(day12::Solution {})),
-        13 => Ok(Box::new(day13::Solution {})),
-        14 => Ok(Box::new(day14::Solution {})),
-        15 => Ok(Box::new(day15::Solution {})),
-        16 => Ok(Box::new(day16::Solution {})),
-        17 => Ok(Box::new(day17::Solution {})),
-        18 => Ok(Box::new(day18::Solution {})),
-        19 => Ok(Box::new(day19::Solution {})),
-        20 => Ok(Box::new(day20::Solution {})),
-        21 => Ok(Box::new(day21::Solution {})),
-        22 => Ok(Box::new(day22::Solution {})),
-        23 => Ok(Box::new(day23::Solution {})),
-        24 => Ok(Box::new(day24::Solution {})),
-        25 => Ok(Box::new(day25::Solution {})),
-        _ => Err(AocRunError::UnregistedProblem(problem)),
+use crate::problem::{Part, ProblemSolution};
+
+// Everything known about a registered day
+pub struct DayInfo {
+    pub year: u16,
+    pub problem: usize,
+    pub title: &'static str,
+    // The parts that have been implemented, e.g. "ab", "a" or ""
+    parts: &'static str,
+    pub tags: &'static [&'static str],
+    // Defaults for the parameters the day reads from its context
+    pub params: &'static [(&'static str, i64)],
+    // Whether the day sees its input exactly as stored, for days where whitespace matters
+    pub raw_input: bool,
+    solution: fn() -> Box<dyn ProblemSolution>,
+}
+
+impl DayInfo {
+    pub fn is_implemented(&self, part: Part) -> bool {
+        let part = match part {
+            Part::A => 'a',
+            Part::B => 'b',
+        };
+        self.parts.contains(part)
+    }
+}
+
+#[cfg(test)]
+impl DayInfo {
+    // A stub day outside the table, for testing how its settings are used
+    pub fn stub(year: u16, problem: usize, raw_input: bool) -> Self {
+        DayInfo {
+            year,
+            problem,
+            title: "",
+            parts: "",
+            tags: &[],
+            params: &[],
+            raw_input,
+            solution: || Box::new(day25::Solution {}),
+        }
+    }
+}
+
+// Optional flags in the register_days! table default to off
+macro_rules! or_false {
+    () => {
+        false
+    };
+    ($value:literal) => {
+        $value
+    };
+}
+
+// Declares the module for each day along with its entry in DAYS
+macro_rules! register_days {
+    ($(
+        ($year:literal, $problem:literal) => $module:ident {
+            title: $title:literal,
+            parts: $parts:literal,
+            tags: [$($tag:literal),* $(,)?],
+            $(params: { $($param:ident: $value:literal),* $(,)? },)?
+            $(raw_input: $raw_input:literal,)?
+        },
+    )*) => {
+        $(mod $module;)*
+
+        pub const DAYS: &[DayInfo] = &[$(
+            DayInfo {
+                year: $year,
+                problem: $problem,
+                title: $title,
+                parts: $parts,
+                tags: &[$($tag),*],
+                params: &[$($((stringify!($param), $value)),*)?],
+                raw_input: or_false!($($raw_input)?),
+                solution: || Box::new($module::Solution {}),
+            },
+        )*];
+    };
+}
+
+// Entries are kept in order of year then day
+register_days! {
+    (2020, 1) => day01 {
+        title: "Report Repair",
+        parts: "ab",
+        tags: ["search"],
+        params: { target: 2020 },
+    },
+    (2020, 2) => day02 {
+        title: "Password Philosophy",
+        parts: "ab",
+        tags: ["parsing"],
+    },
+    (2020, 3) => day03 {
+        title: "Toboggan Trajectory",
+        parts: "ab",
+        tags: ["grid"],
+    },
+    (2020, 4) => day04 {
+        title: "Passport Processing",
+        parts: "ab",
+        tags: ["parsing", "validation"],
+    },
+    (2020, 5) => day05 {
+        title: "Binary Boarding",
+        parts: "ab",
+        tags: ["binary"],
+    },
+    (2020, 6) => day06 {
+        title: "Custom Customs",
+        parts: "ab",
+        tags: ["sets"],
+    },
+    (2020, 7) => day07 {
+        title: "Handy Haversacks",
+        parts: "ab",
+        tags: ["parsing", "graph"],
+    },
+    (2020, 8) => day08 {
+        title: "Handheld Halting",
+        parts: "ab",
+        tags: ["interpreter"],
+    },
+    (2020, 9) => day09 {
+        title: "Encoding Error",
+        parts: "ab",
+        tags: ["search"],
+        params: { lookback: 25 },
+    },
+    (2020, 10) => day10 {
+        title: "Adapter Array",
+        parts: "ab",
+        tags: ["dynamic programming"],
+    },
+    (2020, 11) => day11 {
+        title: "Seating System",
+        parts: "ab",
+        tags: ["grid", "simulation"],
+    },
+    (2020, 12) => day12 {
+        title: "Rain Risk",
+        parts: "ab",
+        tags: ["geometry", "simulation"],
+    },
+    (2020, 13) => day13 {
+        title: "Shuttle Search",
+        parts: "ab",
+        tags: ["number theory"],
+    },
+    (2020, 14) => day14 {
+        title: "Docking Data",
+        parts: "ab",
+        tags: ["bit manipulation"],
+    },
+    (2020, 15) => day15 {
+        title: "Rambunctious Recitation",
+        parts: "ab",
+        tags: ["simulation"],
+        params: { turns_a: 2020, turns_b: 30000000 },
+    },
+    (2020, 16) => day16 {
+        title: "Ticket Translation",
+        parts: "ab",
+        tags: ["parsing", "constraint solving"],
+    },
+    (2020, 17) => day17 {
+        title: "Conway Cubes",
+        parts: "ab",
+        tags: ["grid", "simulation"],
+        params: { cycles: 6 },
+    },
+    (2020, 18) => day18 {
+        title: "Operation Order",
+        parts: "ab",
+        tags: ["parsing"],
+    },
+    (2020, 19) => day19 {
+        title: "Monster Messages",
+        parts: "ab",
+        tags: ["parsing"],
+    },
+    (2020, 20) => day20 {
+        title: "Jurassic Jigsaw",
+        parts: "",
+        tags: ["grid"],
+    },
+    (2020, 21) => day21 {
+        title: "Allergen Assessment",
+        parts: "",
+        tags: ["parsing", "constraint solving"],
+    },
+    (2020, 22) => day22 {
+        title: "Crab Combat",
+        parts: "",
+        tags: ["simulation"],
+    },
+    (2020, 23) => day23 {
+        title: "Crab Cups",
+        parts: "",
+        tags: ["simulation"],
+    },
+    (2020, 24) => day24 {
+        title: "Lobby Layout",
+        parts: "",
+        tags: ["grid"],
+    },
+    (2020, 25) => day25 {
+        title: "Combo Breaker",
+        parts: "",
+        tags: ["number theory"],
+    },
+}
+
+pub fn find(year: u16, problem: usize) -> Option<&'static DayInfo> {
+    DAYS.iter()
+        .find(|day| day.year == year && day.problem == problem)
+}
+
+pub fn get_solution(year: u16, problem: usize) -> Result<Box<dyn ProblemSolution>, AocRunError> {
+    find(year, problem)
+        .map(|day| (day.solution)())
+        .ok_or(AocRunError::UnregistedProblem(year, problem))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{Context, Params};
+    use crate::problem::Answer;
+
+    // The parts in the table are kept by hand, so check them against the solutions.
+    // Implemented parts get an empty input, which only has to not be NotImplemented.
+    #[test]
+    fn parts_match_solutions() {
+        for day in DAYS {
+            let solution = (day.solution)();
+            let ctx = Context::new(Params::for_day(day.year, day.problem));
+            for part in [Part::A, Part::B] {
+                let answer = solution.solve(part, "", &ctx);
+                assert_eq!(
+                    matches!(answer, Ok(Answer::NotImplemented)),
+                    !day.is_implemented(part),
+                    "{}/{} part {part} does not match its parts in register_days!",
+                    day.year,
+                    day.problem
+                );
+            }
+        }
     }
 }
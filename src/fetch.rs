@@ -0,0 +1,374 @@
+// Talks to the Advent of Code website, e.g. to download puzzle inputs.
+// The site asks that automated tools identify themselves and avoid hammering the server,
+// so every request carries a User-Agent and requests are spaced out by a minimum interval.
+use colored::Colorize;
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const BASE_URL: &str = "https://adventofcode.com";
+const USER_AGENT: &str = concat!(
+    "aoc_2020/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/tomchaplin/aoc_2020)"
+);
+const DEFAULT_MIN_INTERVAL: Duration = Duration::from_secs(5);
+const SESSION_VAR: &str = "AOC_SESSION";
+
+#[derive(Debug)]
+pub enum FetchError {
+    NoSession(String),
+    LoggedOut,
+    // A successful response that isn't an input, e.g. a request for a day that hasn't unlocked
+    NotAnInput(String),
+    Http(u16, String),
+    Network(String),
+    Io(String),
+}
+
+impl Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let explanation = match self {
+            FetchError::NoSession(path) => {
+                format!("No {SESSION_VAR} found in the environment or in {path}")
+            }
+            FetchError::LoggedOut => {
+                "The server did not accept the session cookie, it may have expired".to_string()
+            }
+            FetchError::NotAnInput(body) => {
+                let first_line = body.trim().lines().next().unwrap_or_default();
+                format!("The server did not send a puzzle input: {first_line}")
+            }
+            FetchError::Http(status, body) => {
+                format!("Request failed with status {status}: {}", body.trim())
+            }
+            FetchError::Network(msg) => format!("Request failed: {msg}"),
+            FetchError::Io(msg) => format!("Could not save the download: {msg}"),
+        };
+        explanation.bold().red().fmt(f)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum FetchOutcome {
+    Downloaded,
+    // A good copy was already on disk, so nothing was requested
+    Cached,
+}
+
+// Reads the session cookie from AOC_SESSION, falling back to a line
+// AOC_SESSION="<cookie>" in the given .env file
+pub fn read_session(env_path: &Path) -> Result<String, FetchError> {
+    if let Ok(session) = std::env::var(SESSION_VAR) {
+        if !session.trim().is_empty() {
+            return Ok(session.trim().to_string());
+        }
+    }
+    let no_session = || FetchError::NoSession(env_path.display().to_string());
+    let contents = fs::read_to_string(env_path).map_err(|_e| no_session())?;
+    contents
+        .lines()
+        .filter_map(|line| {
+            line.trim()
+                .strip_prefix(SESSION_VAR)?
+                .trim()
+                .strip_prefix('=')
+        })
+        .map(|value| value.trim().trim_matches(|c| c == '"' || c == '\''))
+        .find(|value| !value.is_empty())
+        .map(str::to_string)
+        .ok_or_else(no_session)
+}
+
+// The site answers some failures with a 200 and an explanation instead of the input,
+// so anything that looks like HTML or an error message is not a usable input
+fn looks_like_input(contents: &str) -> bool {
+    let trimmed = contents.trim_start();
+    !trimmed.is_empty()
+        && !trimmed.starts_with('<')
+        && !is_logged_out_message(contents)
+        && !contents.contains("Please don't repeatedly request this endpoint")
+}
+
+fn is_logged_out_message(contents: &str) -> bool {
+    contents.contains("Please log in")
+}
+
+pub struct Fetcher {
+    base_url: String,
+    session: String,
+    agent: ureq::Agent,
+    // Records when the last request was made, so the interval holds across invocations
+    throttle_file: PathBuf,
+    min_interval: Duration,
+}
+
+impl Fetcher {
+    pub fn new(
+        base_url: &str,
+        session: String,
+        throttle_file: PathBuf,
+        min_interval: Duration,
+    ) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .user_agent(USER_AGENT)
+            .timeout(Duration::from_secs(30))
+            .build();
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session,
+            agent,
+            throttle_file,
+            min_interval,
+        }
+    }
+
+    // Connects to the real site, with the session read as in read_session
+    pub fn for_site(data_dir: &Path) -> Result<Self, FetchError> {
+        let session = read_session(Path::new(".env"))?;
+        Ok(Self::new(
+            BASE_URL,
+            session,
+            data_dir.join(".last_fetch"),
+            DEFAULT_MIN_INTERVAL,
+        ))
+    }
+
+    // Downloads the input for a day to `path`, unless a good copy is already there
+    pub fn fetch_input(
+        &self,
+        year: u16,
+        problem: usize,
+        path: &Path,
+    ) -> Result<FetchOutcome, FetchError> {
+        if fs::read_to_string(path).is_ok_and(|contents| looks_like_input(&contents)) {
+            return Ok(FetchOutcome::Cached);
+        }
+        let body = self.get(&format!("/{year}/day/{problem}/input"))?;
+        if is_logged_out_message(&body) {
+            return Err(FetchError::LoggedOut);
+        }
+        if !looks_like_input(&body) {
+            return Err(FetchError::NotAnInput(body));
+        }
+        write_atomically(path, &body)?;
+        Ok(FetchOutcome::Downloaded)
+    }
+
+    // The puzzle description page, which includes part B once part A is solved
+    pub fn fetch_puzzle(&self, year: u16, problem: usize) -> Result<String, FetchError> {
+        self.get(&format!("/{year}/day/{problem}"))
+    }
+
+    fn get(&self, url_path: &str) -> Result<String, FetchError> {
+        let response = self.request("GET", url_path).call();
+        self.record_request();
+        read_response(response)
+    }
+
+    pub fn post_form(&self, url_path: &str, form: &[(&str, &str)]) -> Result<String, FetchError> {
+        let response = self.request("POST", url_path).send_form(form);
+        self.record_request();
+        read_response(response)
+    }
+
+    // Waits until the throttle allows another request
+    fn request(&self, method: &str, url_path: &str) -> ureq::Request {
+        self.wait_for_throttle();
+        self.agent
+            .request(method, &format!("{}{url_path}", self.base_url))
+            .set("Cookie", &format!("session={}", self.session))
+    }
+
+    fn wait_for_throttle(&self) {
+        let Some(last) = fs::read_to_string(&self.throttle_file)
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok())
+        else {
+            return;
+        };
+        let next = UNIX_EPOCH + Duration::from_millis(last) + self.min_interval;
+        if let Ok(wait) = next.duration_since(SystemTime::now()) {
+            thread::sleep(wait);
+        }
+    }
+
+    fn record_request(&self) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        // Failing to record only means the next request might not wait
+        if let Some(dir) = self.throttle_file.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        let _ = fs::write(&self.throttle_file, now.to_string());
+    }
+}
+
+fn read_response(response: Result<ureq::Response, ureq::Error>) -> Result<String, FetchError> {
+    match response {
+        Ok(response) => response
+            .into_string()
+            .map_err(|e| FetchError::Network(e.to_string())),
+        Err(ureq::Error::Status(status, response)) => {
+            let body = response.into_string().unwrap_or_default();
+            if is_logged_out_message(&body) {
+                Err(FetchError::LoggedOut)
+            } else {
+                Err(FetchError::Http(status, body))
+            }
+        }
+        Err(ureq::Error::Transport(e)) => Err(FetchError::Network(e.to_string())),
+    }
+}
+
+// Writes via a temporary file so that an interrupted download never leaves a partial input
+fn write_atomically(path: &Path, contents: &str) -> Result<(), FetchError> {
+    let io_error = |e: std::io::Error| FetchError::Io(format!("{}: {e}", path.display()));
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(io_error)?;
+    }
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, contents).map_err(io_error)?;
+    fs::rename(&tmp_path, path).map_err(io_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::TestServer;
+    use std::time::Instant;
+    use tempfile::TempDir;
+
+    const INPUT: &str = "1721\n979\n366\n";
+
+    fn fetcher(server: &TestServer, dir: &TempDir, min_interval: Duration) -> Fetcher {
+        Fetcher::new(
+            &server.url(),
+            "abc123".to_string(),
+            dir.path().join(".last_fetch"),
+            min_interval,
+        )
+    }
+
+    #[test]
+    fn downloads_input_with_session_and_user_agent() {
+        let server = TestServer::start(vec![(200, INPUT.to_string())]);
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("2020/inputs/01.txt");
+        let outcome = fetcher(&server, &dir, Duration::ZERO).fetch_input(2020, 1, &path);
+        assert_eq!(outcome.unwrap(), FetchOutcome::Downloaded);
+        assert_eq!(fs::read_to_string(&path).unwrap(), INPUT);
+
+        let request = server.requests().remove(0);
+        assert!(request.starts_with("GET /2020/day/1/input "));
+        let request = request.to_lowercase();
+        assert!(request.contains("cookie: session=abc123"));
+        assert!(request.contains("user-agent: aoc_2020/"));
+    }
+
+    #[test]
+    fn keeps_good_cached_input() {
+        let server = TestServer::start(vec![(200, "new input\n".to_string())]);
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("01.txt");
+        fs::write(&path, INPUT).unwrap();
+        let outcome = fetcher(&server, &dir, Duration::ZERO).fetch_input(2020, 1, &path);
+        assert_eq!(outcome.unwrap(), FetchOutcome::Cached);
+        assert_eq!(fs::read_to_string(&path).unwrap(), INPUT);
+        assert!(server.requests().is_empty());
+    }
+
+    #[test]
+    fn replaces_bad_cached_input() {
+        let server = TestServer::start(vec![(200, INPUT.to_string())]);
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("01.txt");
+        fs::write(&path, "<!DOCTYPE html>\n<html></html>\n").unwrap();
+        let outcome = fetcher(&server, &dir, Duration::ZERO).fetch_input(2020, 1, &path);
+        assert_eq!(outcome.unwrap(), FetchOutcome::Downloaded);
+        assert_eq!(fs::read_to_string(&path).unwrap(), INPUT);
+    }
+
+    #[test]
+    fn detects_logged_out_response() {
+        let body = "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n";
+        let server = TestServer::start(vec![(400, body.to_string())]);
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("01.txt");
+        let outcome = fetcher(&server, &dir, Duration::ZERO).fetch_input(2020, 1, &path);
+        assert!(matches!(outcome, Err(FetchError::LoggedOut)));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn rejects_html_served_as_input() {
+        let body = "<!DOCTYPE html>\n<html><body>Log in</body></html>\n";
+        let server = TestServer::start(vec![(200, body.to_string())]);
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("01.txt");
+        let outcome = fetcher(&server, &dir, Duration::ZERO).fetch_input(2020, 1, &path);
+        assert!(matches!(outcome, Err(FetchError::NotAnInput(_))));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn reports_locked_day_served_with_200() {
+        let body = "Please don't repeatedly request this endpoint before it unlocks!\n";
+        let server = TestServer::start(vec![(200, body.to_string())]);
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("25.txt");
+        let outcome = fetcher(&server, &dir, Duration::ZERO).fetch_input(2020, 25, &path);
+        let Err(error) = outcome else {
+            panic!("a locked day is not an input");
+        };
+        assert!(matches!(error, FetchError::NotAnInput(_)));
+        assert!(error.to_string().contains("before it unlocks"));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn reports_non_200_status() {
+        let body = "Please don't repeatedly request this endpoint before it unlocks!\n";
+        let server = TestServer::start(vec![(404, body.to_string())]);
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("25.txt");
+        let outcome = fetcher(&server, &dir, Duration::ZERO).fetch_input(2020, 25, &path);
+        assert!(matches!(outcome, Err(FetchError::Http(404, _))));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn spaces_out_requests() {
+        let server = TestServer::start(vec![(200, INPUT.to_string()), (200, INPUT.to_string())]);
+        let dir = TempDir::new().unwrap();
+        let fetcher = fetcher(&server, &dir, Duration::from_millis(300));
+        let start = Instant::now();
+        fetcher
+            .fetch_input(2020, 1, &dir.path().join("01.txt"))
+            .unwrap();
+        fetcher
+            .fetch_input(2020, 2, &dir.path().join("02.txt"))
+            .unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(300));
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[test]
+    fn reads_session_from_env_file() {
+        let dir = TempDir::new().unwrap();
+        let env_path = dir.path().join(".env");
+        fs::write(&env_path, "OTHER=1\nAOC_SESSION=\"abc123\"\n").unwrap();
+        // The environment variable takes priority, so only check the file when it is unset
+        if std::env::var(SESSION_VAR).is_err() {
+            assert_eq!(read_session(&env_path).unwrap(), "abc123");
+            assert!(matches!(
+                read_session(&dir.path().join("missing")),
+                Err(FetchError::NoSession(_))
+            ));
+        }
+    }
+}
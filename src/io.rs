@@ -1,123 +1,1063 @@
 use aoc_parse::{parser, prelude::*};
 use colored::Colorize;
+use serde::{Serialize, Serializer};
+use std::cell::Cell;
 use std::fmt::Display;
 use std::fs;
-use std::str::FromStr;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
 
+use crate::answers::{AnswerCheck, ExpectedAnswers};
+use crate::bench::{BenchReport, BenchStats};
+use crate::context::{Context, LogLevel, Params, TerminalSink};
 use crate::days;
+use crate::memory::{self, MemoryStats};
+use crate::normalize::Normalization;
+use crate::problem::{Answer, ParsedInput, Part, ProblemSolution, SolveError};
 
+#[derive(Debug)]
 pub enum AocRunError {
     NoFile(String),
-    UnregistedProblem(usize),
+    BadAnswerFile(String),
+    BadLedgerFile(String),
+    UnregistedProblem(u16, usize),
     BadRunCode(String),
+    ParseFailure(usize, Part, String),
+    NoAnswer(usize, Part),
+    UnsupportedInput(usize, Part, String),
+    Panicked(usize, Part, String),
+    MissingParam(usize, Part, String),
+    Cancelled(usize, Part),
+    TimedOut(usize, Part, Duration),
+    BadParam(String),
 }
 
-impl Display for AocRunError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let explanation = match self {
+impl AocRunError {
+    fn from_solve_error(problem: usize, part: Part, error: SolveError) -> Self {
+        match error {
+            SolveError::Parse(msg) => AocRunError::ParseFailure(problem, part, msg),
+            SolveError::NoAnswer => AocRunError::NoAnswer(problem, part),
+            SolveError::UnsupportedInput(msg) => AocRunError::UnsupportedInput(problem, part, msg),
+            SolveError::MissingParam(name) => AocRunError::MissingParam(problem, part, name),
+            SolveError::Cancelled => AocRunError::Cancelled(problem, part),
+        }
+    }
+
+    // The explanation without any colour, e.g. for machine readable output
+    pub fn message(&self) -> String {
+        match self {
             AocRunError::NoFile(path) => format!("Could not find problem input at path {path}"),
-            AocRunError::UnregistedProblem(problem) => {
-                format!("No solution registered for problem {problem}")
+            AocRunError::BadAnswerFile(path) => {
+                format!("Could not parse expected answers at path {path}")
+            }
+            AocRunError::BadLedgerFile(path) => {
+                format!("Could not parse submission attempts at path {path}")
+            }
+            AocRunError::UnregistedProblem(year, problem) => {
+                format!("No solution registered for {year} problem {problem}")
             }
             AocRunError::BadRunCode(code) => format!("Could not parse run code \"{code}\""),
-        };
-        explanation.bold().red().fmt(f)
+            AocRunError::ParseFailure(problem, part, msg) => {
+                format!("Failed to parse input for problem {problem}{part}: {msg}")
+            }
+            AocRunError::NoAnswer(problem, part) => {
+                format!("No answer found for problem {problem}{part}")
+            }
+            AocRunError::UnsupportedInput(problem, part, msg) => {
+                format!("Unsupported input for problem {problem}{part}: {msg}")
+            }
+            AocRunError::Panicked(problem, part, msg) => {
+                format!("Problem {problem}{part} panicked: {msg}")
+            }
+            AocRunError::MissingParam(problem, part, name) => {
+                format!("Problem {problem}{part} needs a default for parameter {name}")
+            }
+            AocRunError::Cancelled(problem, part) => {
+                format!("Problem {problem}{part} was cancelled")
+            }
+            AocRunError::TimedOut(problem, part, timeout) => {
+                format!("Problem {problem}{part} timed out after {timeout:.2?}")
+            }
+            AocRunError::BadParam(header) => format!("Could not parse parameters \"{header}\""),
+        }
+    }
+}
+
+// Why a solution failed, before it is known which part it failed in
+#[derive(Debug, Clone)]
+enum Failure {
+    Error(SolveError),
+    Panic(String),
+    TimedOut(Duration),
+}
+
+impl Failure {
+    fn for_part(self, problem: usize, part: Part) -> AocRunError {
+        match self {
+            Failure::Error(e) => AocRunError::from_solve_error(problem, part, e),
+            Failure::Panic(msg) => AocRunError::Panicked(problem, part, msg),
+            Failure::TimedOut(timeout) => AocRunError::TimedOut(problem, part, timeout),
+        }
     }
 }
 
+thread_local! {
+    // Whether this thread is running a solution, whose panics are caught by attempt
+    static SOLVING: Cell<bool> = const { Cell::new(false) };
+    // Where the last caught panic happened, recorded by the quiet panic hook
+    static PANIC_LOCATION: Cell<Option<String>> = const { Cell::new(None) };
+}
+
+// Stops panics in solutions from being printed in the middle of the results, since they
+// are reported as Panicked instead. Panics anywhere else are printed as usual.
+pub fn install_quiet_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if SOLVING.get() {
+            let location = info
+                .location()
+                .map(|l| format!("{}:{}", l.file(), l.line()));
+            PANIC_LOCATION.set(location);
+        } else {
+            default_hook(info)
+        }
+    }));
+}
+
+// Runs some of a solution, catching a panic so that it can't take down the other part
+fn attempt<T>(f: impl FnOnce() -> Result<T, SolveError>) -> Result<T, Failure> {
+    let was_solving = SOLVING.replace(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    SOLVING.set(was_solving);
+    match result {
+        Ok(result) => result.map_err(Failure::Error),
+        Err(payload) => {
+            let message = panic_message(payload.as_ref());
+            match PANIC_LOCATION.take() {
+                Some(location) => Err(Failure::Panic(format!("{message} at {location}"))),
+                None => Err(Failure::Panic(message)),
+            }
+        }
+    }
+}
+
+// Runs some of a solution on a worker thread with its own context, giving up once the
+// timeout has passed. The solution is asked to stop then, but if it doesn't check for
+// cancellation its thread is left running in the background.
+fn attempt_within<T: Send + 'static>(
+    timeout: Option<Duration>,
+    ctx: Context,
+    f: impl FnOnce(&Context) -> Result<T, SolveError> + Send + 'static,
+) -> Result<T, Failure> {
+    let Some(timeout) = timeout else {
+        return attempt(|| f(&ctx));
+    };
+    let ctx = Arc::new(ctx);
+    let worker_ctx = ctx.clone();
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || sender.send(attempt(|| f(&worker_ctx))));
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => {
+            ctx.cancel();
+            Err(Failure::TimedOut(timeout))
+        }
+        Err(RecvTimeoutError::Disconnected) => Err(Failure::Panic(
+            "worker thread stopped without an answer".to_string(),
+        )),
+    }
+}
+
+// What each part is solved from, shared with the worker threads
+#[derive(Clone)]
+struct Job {
+    solution: Arc<dyn ProblemSolution>,
+    input: Arc<str>,
+    // Only for solutions that parse once, see ProblemSolution::parse_once
+    parsed: Option<Arc<Result<ParsedInput, Failure>>>,
+}
+
+impl Job {
+    fn new(solution: Box<dyn ProblemSolution>, input: String) -> Self {
+        Self {
+            solution: solution.into(),
+            input: input.into(),
+            parsed: None,
+        }
+    }
+
+    // Returns None if the solution parses in each part instead.
+    // Memory is only counted with --memory, and only if parsing succeeded.
+    fn parse_once(
+        &self,
+        ctx: &Context,
+        timeout: Option<Duration>,
+    ) -> Option<(Result<ParsedInput, Failure>, Option<MemoryStats>)> {
+        let job = self.clone();
+        let result = attempt_within(timeout, ctx.for_worker(), move |ctx| {
+            let (parsed, memory) = memory::measure(|| job.solution.parse_once(&job.input, ctx));
+            parsed
+                .transpose()
+                .map(|parsed| parsed.map(|parsed| (parsed, memory)))
+        })
+        .transpose()?;
+        Some(match result {
+            Ok((parsed, memory)) => (Ok(parsed), memory),
+            Err(failure) => (Err(failure), None),
+        })
+    }
+}
+
+impl Display for AocRunError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.message().bold().red().fmt(f)
+    }
+}
+
+impl Serialize for AocRunError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.message())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum InputOverride {
+    File(PathBuf),
+    Stdin,
+}
+
+pub const DEFAULT_YEAR: u16 = 2020;
+
+// Settings shared by every run code in a single invocation
+#[derive(Debug, Clone)]
+pub struct RunOptions {
+    // Year used by run codes that don't name one
+    pub year: u16,
+    pub data_dir: PathBuf,
+    // Replaces the input that the run code would otherwise read from the data directory
+    pub input: Option<InputOverride>,
+    // Number of threads to run days and parts on, 1 runs everything in turn
+    pub jobs: usize,
+    // Set with --param, these override every day's defaults and example headers
+    pub params: Params,
+    // Which of the solutions' log messages to print, set with --log
+    pub log_level: LogLevel,
+    // Whether solutions should draw their visualizations, set with --visualize
+    pub visualize: bool,
+    // How long each part may take before it is reported as timed out, set with --timeout
+    pub timeout: Option<Duration>,
+    // Whether to count each part's allocations, set with --memory
+    pub memory: bool,
+    // How inputs are tidied up before they are solved, set with --normalize
+    pub normalization: Normalization,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            year: DEFAULT_YEAR,
+            data_dir: PathBuf::from("data"),
+            input: None,
+            jobs: 1,
+            params: Params::default(),
+            log_level: LogLevel::default(),
+            visualize: false,
+            timeout: None,
+            memory: false,
+            normalization: Normalization::default(),
+        }
+    }
+}
+
+pub type PartResult = Result<Answer, AocRunError>;
+
+#[derive(Debug)]
+pub struct PartOutcome {
+    pub result: PartResult,
+    pub duration: Duration,
+    pub check: AnswerCheck,
+    // Only counted with --memory, and only for parts that finished
+    pub memory: Option<MemoryStats>,
+}
+
+impl PartOutcome {
+    // For parts that the register_days! table marks as not implemented, which aren't run
+    fn not_implemented() -> Self {
+        PartOutcome {
+            result: Ok(Answer::NotImplemented),
+            duration: Duration::ZERO,
+            check: AnswerCheck::Unverified,
+            memory: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    Puzzle,
+    // None is the unnamed example NN.txt, Some(name) is the named example NN-name.txt
+    Example(Option<String>),
+    // Every example for the problem, see RunCode::expand_examples
+    AllExamples,
+}
+
 #[derive(Debug)]
 pub struct RunCode<const SOLVED: bool> {
+    year: u16,
     problem: usize,
     run_a: bool,
     run_b: bool,
-    as_example: bool,
-    result_a: Option<String>,
-    result_b: Option<String>,
+    input: InputSource,
+    load_duration: Option<Duration>,
+    parse_duration: Option<Duration>,
+    parse_memory: Option<MemoryStats>,
+    result_a: Option<PartOutcome>,
+    result_b: Option<PartOutcome>,
 }
 
-impl RunCode<false> {
-    pub fn init_run_all(problem: usize) -> Self {
-        Self {
-            problem,
-            run_a: true,
-            run_b: true,
-            as_example: false,
-            result_a: None,
-            result_b: None,
+impl<const SOLVED: bool> RunCode<SOLVED> {
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    pub fn problem(&self) -> usize {
+        self.problem
+    }
+
+    pub fn is_puzzle_input(&self) -> bool {
+        self.input == InputSource::Puzzle
+    }
+
+    pub fn example_name(&self) -> Option<&str> {
+        match &self.input {
+            InputSource::Example(Some(name)) => Some(name),
+            _ => None,
         }
     }
-}
 
-impl FromStr for RunCode<false> {
-    type Err = AocRunError;
+    // Only years other than the default are worth mentioning
+    fn year_prefix(&self, separator: &str) -> String {
+        if self.year == DEFAULT_YEAR {
+            String::new()
+        } else {
+            format!("{}{separator}", self.year)
+        }
+    }
+
+    // Short form of the run code, without the parts, e.g. 13, e13, e13:large or 2019/13
+    pub fn label(&self) -> String {
+        let year = self.year_prefix("/");
+        let problem = self.problem;
+        match &self.input {
+            InputSource::Puzzle => format!("{year}{problem}"),
+            InputSource::Example(Some(name)) => format!("{year}e{problem}:{name}"),
+            InputSource::Example(None) | InputSource::AllExamples => format!("{year}e{problem}"),
+        }
+    }
+
+    // Which input was used, e.g. puzzle, example or example:large
+    pub fn input_kind(&self) -> String {
+        match &self.input {
+            InputSource::Puzzle => "puzzle".to_string(),
+            InputSource::Example(None) | InputSource::AllExamples => "example".to_string(),
+            InputSource::Example(Some(name)) => format!("example:{name}"),
+        }
+    }
+
+    // The input for reports, which is where it came from if --input replaced it
+    pub fn input_label(&self, options: &RunOptions) -> String {
+        match &options.input {
+            Some(InputOverride::Stdin) => "stdin".to_string(),
+            Some(InputOverride::File(path)) => format!("file:{}", path.display()),
+            None => self.input_kind(),
+        }
+    }
+
+    // e.g. Day 13: Shuttle Search (example 3), the title is left out for unregistered days
+    pub fn description(&self) -> String {
+        let year = self.year_prefix(" ");
+        let problem = self.problem;
+        let title = days::find(self.year, problem)
+            .filter(|day| !day.title.is_empty())
+            .map(|day| format!(": {}", day.title))
+            .unwrap_or_default();
+        match &self.input {
+            InputSource::Puzzle => format!("{year}Day {problem}{title}"),
+            InputSource::Example(Some(name)) => {
+                format!("{year}Day {problem}{title} (example {name})")
+            }
+            InputSource::Example(None) | InputSource::AllExamples => {
+                format!("{year}Day {problem}{title} (example)")
+            }
+        }
+    }
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+impl RunCode<false> {
+    // Parses a comma separated list of run codes, each of which is one of
+    // - `all` for both parts of every day
+    // - a day or range of days, e.g. `4` or `1-10`
+    // optionally prefixed with `e` to use examples and suffixed with `a`, `b` or `ab`.
+    // A single example day may also be followed by `:<name>` to pick a named example.
+    // Any code can be prefixed with `<year>/` to use a year other than `default_year`.
+    pub fn parse_codes(s: &str, default_year: u16) -> Result<Vec<Self>, AocRunError> {
         let ab_parser = parser!({
             "a" => (true, false),
              "b" => (false, true),
              "ab" => (true, true),
              "" => (true, true)
         });
-        let example_parser = parser!({"e" => true, "" => false});
-        let code_parser = parser!(example_parser usize ab_parser);
-        let (as_example, problem, (run_a, run_b)) = code_parser
+        let range_parser = parser!({
+            first:usize "-" last:usize => (first, last),
+            problem:usize => (problem, problem),
+        });
+        let name_parser = parser!(string({alnum, "_" => '_', "-" => '-'}+));
+        let code_parser = parser!({
+            "all" => (InputSource::Puzzle, (1, 25), (true, true), None),
+            "e" range:range_parser parts:ab_parser name:(":" name_parser)? => {
+                (InputSource::AllExamples, range, parts, name)
+            },
+            range:range_parser parts:ab_parser => (InputSource::Puzzle, range, parts, None),
+        });
+        let code_parser = parser!((u16 "/")? code_parser);
+        let codes = parser!(repeat_sep(code_parser, ","))
             .parse(s)
             .map_err(|_err| AocRunError::BadRunCode(s.to_string()))?;
-        Ok(RunCode {
+
+        let mut run_codes = vec![];
+        for (year, (input, (first, last), (run_a, run_b), name)) in codes {
+            let year = year.unwrap_or(default_year);
+            // Names only make sense for a single day
+            if first > last || (name.is_some() && first != last) {
+                return Err(AocRunError::BadRunCode(s.to_string()));
+            }
+            let input = match name {
+                Some(name) => InputSource::Example(Some(name)),
+                None => input,
+            };
+            run_codes.extend((first..=last).map(|problem| RunCode {
+                year,
+                problem,
+                run_a,
+                run_b,
+                input: input.clone(),
+                load_duration: None,
+                parse_duration: None,
+                parse_memory: None,
+                result_a: None,
+                result_b: None,
+            }));
+        }
+        Ok(run_codes)
+    }
+}
+
+impl RunCode<false> {
+    fn new(year: u16, problem: usize, input: InputSource) -> Self {
+        RunCode {
+            year,
             problem,
-            run_a,
-            run_b,
-            as_example,
+            run_a: true,
+            run_b: true,
+            input,
+            load_duration: None,
+            parse_duration: None,
+            parse_memory: None,
             result_a: None,
             result_b: None,
-        })
+        }
     }
-}
 
-impl RunCode<false> {
-    fn get_input(&self) -> Result<String, AocRunError> {
-        let foldername = if self.as_example {
-            "examples"
-        } else {
-            "inputs"
-        };
+    pub fn puzzle(year: u16, problem: usize) -> Self {
+        Self::new(year, problem, InputSource::Puzzle)
+    }
+
+    pub fn example(year: u16, problem: usize, name: Option<String>) -> Self {
+        Self::new(year, problem, InputSource::Example(name))
+    }
+
+    // Every example for the problem, see expand_examples
+    pub fn all_examples(year: u16, problem: usize) -> Self {
+        Self::new(year, problem, InputSource::AllExamples)
+    }
+
+    pub fn only(self, part: Part) -> Self {
+        RunCode {
+            run_a: part == Part::A,
+            run_b: part == Part::B,
+            ..self
+        }
+    }
+
+    // Replaces a request for all examples with one run code per example file.
+    // If there are no example files we fall back to the unnamed example,
+    // so that running it reports the missing file.
+    pub fn expand_examples(self, options: &RunOptions) -> Vec<RunCode<false>> {
+        // An overridden input replaces every example
+        if self.input != InputSource::AllExamples || options.input.is_some() {
+            return vec![self];
+        }
+        let mut names = list_examples(&self.year_dir(options), self.problem);
+        if names.is_empty() {
+            names.push(None);
+        }
+        names
+            .into_iter()
+            .map(|name| RunCode {
+                year: self.year,
+                problem: self.problem,
+                run_a: self.run_a,
+                run_b: self.run_b,
+                input: InputSource::Example(name),
+                load_duration: None,
+                parse_duration: None,
+                parse_memory: None,
+                result_a: None,
+                result_b: None,
+            })
+            .collect()
+    }
+
+    // Data for each year lives in its own directory, e.g. data/2020/inputs
+    fn year_dir(&self, options: &RunOptions) -> PathBuf {
+        options.data_dir.join(self.year.to_string())
+    }
+
+    // Name of the input file without the extension, e.g. 13 or 13-large
+    fn file_stem(&self) -> String {
         let problem = self.problem;
-        let path = format!("./data/{foldername}/{problem:02}.txt");
-        fs::read_to_string(&path).map_err(|_e| AocRunError::NoFile(path))
+        match &self.input {
+            InputSource::Example(Some(name)) => format!("{problem:02}-{name}"),
+            _ => format!("{problem:02}"),
+        }
     }
 
-    pub fn run(self) -> Result<RunCode<true>, AocRunError> {
-        let solution = days::get_solution(self.problem)?;
-        let input = self.get_input()?;
-        let result_a = self.run_a.then(|| solution.solve_a(&input)).flatten();
-        let result_b = self.run_b.then(|| solution.solve_b(&input)).flatten();
+    // Where the input lives in the data directory, ignoring any override
+    pub fn input_path(&self, options: &RunOptions) -> PathBuf {
+        let foldername = match self.input {
+            InputSource::Puzzle => "inputs",
+            _ => "examples",
+        };
+        let stem = self.file_stem();
+        self.year_dir(options)
+            .join(foldername)
+            .join(format!("{stem}.txt"))
+    }
+
+    // The files that this run code reads, or None if the input comes from stdin
+    pub fn watched_paths(&self, options: &RunOptions) -> Option<Vec<PathBuf>> {
+        match &options.input {
+            Some(InputOverride::Stdin) => None,
+            Some(InputOverride::File(path)) => Some(vec![path.clone()]),
+            None => Some(vec![self.input_path(options), self.answers_path(options)]),
+        }
+    }
+
+    fn get_input(&self, options: &RunOptions) -> Result<String, AocRunError> {
+        let path = match &options.input {
+            Some(InputOverride::Stdin) => return read_stdin(),
+            Some(InputOverride::File(path)) => path.clone(),
+            None => self.input_path(options),
+        };
+        fs::read_to_string(&path).map_err(|_e| AocRunError::NoFile(resolved_path(&path)))
+    }
+
+    // Reads and normalizes the input, splitting off any header, along with the context
+    // to solve it in
+    pub fn get_input_and_context(
+        &self,
+        options: &RunOptions,
+    ) -> Result<(String, Context), AocRunError> {
+        let normalization = options.normalization.for_day(self.year, self.problem);
+        let input = normalization.apply(self.get_input(options)?);
+        let (header, input) = Params::split_header(&input).map_err(AocRunError::BadParam)?;
+        let mut params = Params::for_day(self.year, self.problem);
+        params.extend(&header);
+        params.extend(&options.params);
+        let sink = TerminalSink::new(self.label());
+        let ctx =
+            Context::new(params).with_output(options.log_level, options.visualize, Box::new(sink));
+        Ok((input.to_string(), ctx))
+    }
+
+    fn get_expected_answers(&self, options: &RunOptions) -> Result<ExpectedAnswers, AocRunError> {
+        // We don't know the answers for an arbitrary input
+        if options.input.is_some() {
+            return Ok(ExpectedAnswers::default());
+        }
+        ExpectedAnswers::load(&self.answers_path(options).to_string_lossy())
+    }
+
+    // Where the expected answers for the input live in the data directory
+    pub fn answers_path(&self, options: &RunOptions) -> PathBuf {
+        let mut path = self.year_dir(options).join("answers");
+        if self.input != InputSource::Puzzle {
+            path.push("examples");
+        }
+        path.push(format!("{}.txt", self.file_stem()));
+        path
+    }
+
+    pub fn parts(&self) -> impl Iterator<Item = Part> {
+        [(Part::A, self.run_a), (Part::B, self.run_b)]
+            .into_iter()
+            .filter_map(|(part, should_run)| should_run.then_some(part))
+    }
+
+    // Each part is isolated so that a panic or timeout in one does not take down the other.
+    // Solutions that parse once are given the result, otherwise the part parses the input.
+    fn solve_part(
+        &self,
+        job: &Job,
+        part: Part,
+        ctx: &Context,
+        timeout: Option<Duration>,
+    ) -> PartOutcome {
+        let start = Instant::now();
+        let result = match job.parsed.as_deref() {
+            // Every part fails the same way when parsing fails
+            Some(Err(failure)) => Err(failure.clone()),
+            _ => {
+                let job = job.clone();
+                // Measured on the thread that solves the part
+                attempt_within(timeout, ctx.for_worker(), move |ctx| {
+                    let (result, memory) = memory::measure(|| match job.parsed.as_deref() {
+                        Some(Ok(parsed)) => job.solution.solve_parsed(part, parsed, ctx),
+                        _ => job.solution.solve(part, &job.input, ctx),
+                    });
+                    result.map(|answer| (answer, memory))
+                })
+            }
+        };
+        let duration = start.elapsed();
+        let (result, memory) = match result {
+            Ok((answer, memory)) => (Ok(answer), memory),
+            Err(failure) => (Err(failure.for_part(self.problem, part)), None),
+        };
+        PartOutcome {
+            result,
+            duration,
+            check: AnswerCheck::Unverified,
+            memory,
+        }
+    }
+
+    pub fn run(&self, options: &RunOptions) -> Result<RunCode<true>, AocRunError> {
+        let solution = days::get_solution(self.year, self.problem)?;
+        let implemented =
+            |part| days::find(self.year, self.problem).is_some_and(|day| day.is_implemented(part));
+        // Stubs are reported as TODO without needing their input
+        if !self.parts().any(implemented) {
+            return Ok(RunCode {
+                year: self.year,
+                problem: self.problem,
+                run_a: self.run_a,
+                run_b: self.run_b,
+                input: self.input.clone(),
+                load_duration: None,
+                parse_duration: None,
+                parse_memory: None,
+                result_a: self.run_a.then(PartOutcome::not_implemented),
+                result_b: self.run_b.then(PartOutcome::not_implemented),
+            });
+        }
+        let load_start = Instant::now();
+        let (input, ctx) = self.get_input_and_context(options)?;
+        let load_duration = load_start.elapsed();
+        let expected = self.get_expected_answers(options)?;
+        let mut job = Job::new(solution, input);
+        let parse_start = Instant::now();
+        let parsed = job.parse_once(&ctx, options.timeout);
+        let parse_duration = parsed.is_some().then(|| parse_start.elapsed());
+        let (parsed, parse_memory) = parsed.unzip();
+        job.parsed = parsed.map(Arc::new);
+        let solve_part = |part| {
+            if !implemented(part) {
+                return PartOutcome::not_implemented();
+            }
+            let mut outcome = self.solve_part(&job, part, &ctx, options.timeout);
+            if let Ok(answer) = &outcome.result {
+                outcome.check = expected.check(part, answer);
+            }
+            outcome
+        };
+        let (result_a, result_b) = if options.jobs > 1 {
+            rayon::join(
+                || self.run_a.then(|| solve_part(Part::A)),
+                || self.run_b.then(|| solve_part(Part::B)),
+            )
+        } else {
+            (
+                self.run_a.then(|| solve_part(Part::A)),
+                self.run_b.then(|| solve_part(Part::B)),
+            )
+        };
         Ok(RunCode {
+            year: self.year,
             problem: self.problem,
             run_a: self.run_a,
             run_b: self.run_b,
-            as_example: self.as_example,
+            input: self.input.clone(),
+            load_duration: Some(load_duration),
+            parse_duration,
+            parse_memory: parse_memory.flatten(),
             result_a,
             result_b,
         })
     }
+
+    // Solves each requested part `iterations` times, stopping at the first error
+    pub fn bench(
+        self,
+        options: &RunOptions,
+        iterations: usize,
+    ) -> Result<BenchReport, AocRunError> {
+        let solution = days::get_solution(self.year, self.problem)?;
+        let (input, ctx) = self.get_input_and_context(options)?;
+        let mut job = Job::new(solution, input);
+        // Parsing is timed on its own, and each part is then timed on the parsed input
+        let mut parse_samples = Vec::with_capacity(iterations);
+        for _ in 0..iterations {
+            let start = Instant::now();
+            let Some((result, _)) = job.parse_once(&ctx, None) else {
+                break;
+            };
+            parse_samples.push(start.elapsed());
+            let failed = result.is_err();
+            job.parsed = Some(Arc::new(result));
+            if failed {
+                break;
+            }
+        }
+        let mut parts = vec![];
+        for part in self.parts() {
+            let mut samples = Vec::with_capacity(iterations);
+            for _ in 0..iterations {
+                let outcome = self.solve_part(&job, part, &ctx, None);
+                match outcome.result {
+                    // Nothing to time for an unimplemented part
+                    Ok(Answer::NotImplemented) => break,
+                    Ok(_) => samples.push(outcome.duration),
+                    Err(e) => return Err(e),
+                }
+            }
+            parts.push((part, BenchStats::from_samples(samples)));
+        }
+        Ok(BenchReport::new(
+            BenchStats::from_samples(parse_samples),
+            parts,
+        ))
+    }
+}
+
+impl RunCode<true> {
+    // Only set for solutions that parse their input once, ahead of both parts
+    pub fn parse_duration(&self) -> Option<Duration> {
+        self.parse_duration
+    }
+
+    // Only counted with --memory, for solutions that parse their input once
+    pub fn parse_memory(&self) -> Option<MemoryStats> {
+        self.parse_memory
+    }
+
+    pub fn outcome(&self, part: Part) -> Option<&PartOutcome> {
+        match part {
+            Part::A => self.result_a.as_ref(),
+            Part::B => self.result_b.as_ref(),
+        }
+    }
+
+    pub fn has_wrong_answer(&self) -> bool {
+        [Part::A, Part::B]
+            .into_iter()
+            .filter_map(|part| self.outcome(part))
+            .any(|outcome| matches!(outcome.check, AnswerCheck::Wrong(_)))
+    }
+}
+
+// Whether a name can be used for an example file, and selected with eN:<name>
+pub fn is_example_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+// Finds the unnamed example NN.txt and every named example NN-name.txt
+fn list_examples(data_dir: &Path, problem: usize) -> Vec<Option<String>> {
+    let Ok(entries) = fs::read_dir(data_dir.join("examples")) else {
+        return vec![];
+    };
+    let prefix = format!("{problem:02}");
+    let mut names: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter_map(|file_name| {
+            let rest = file_name.strip_prefix(&prefix)?.strip_suffix(".txt")?;
+            if rest.is_empty() {
+                Some(None)
+            } else {
+                rest.strip_prefix('-').map(|name| Some(name.to_string()))
+            }
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+// Reports paths in full so it is clear where we actually looked
+fn resolved_path(path: &Path) -> String {
+    std::path::absolute(path)
+        .unwrap_or_else(|_e| path.to_path_buf())
+        .display()
+        .to_string()
+}
+
+// Stdin can only be read once, so it is shared between every run code
+fn read_stdin() -> Result<String, AocRunError> {
+    static STDIN: OnceLock<Option<String>> = OnceLock::new();
+    STDIN
+        .get_or_init(|| std::io::read_to_string(std::io::stdin()).ok())
+        .clone()
+        .ok_or_else(|| AocRunError::NoFile("<stdin>".to_string()))
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        msg.to_string()
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+fn indent(block: &str) -> String {
+    block
+        .lines()
+        .map(|line| format!("    {line}"))
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 impl Display for RunCode<true> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.run_a {
-            let solution_str = match self.result_a.as_ref() {
-                Some(s) => s.to_string().bold().cyan(),
-                None => "TODO".to_string().dimmed(),
+        if let Some(load_duration) = self.load_duration {
+            let timing = match (self.parse_duration, self.parse_memory) {
+                (Some(parse_duration), Some(memory)) => format!(
+                    "(loaded in {load_duration:.2?}, parsed in {parse_duration:.2?}, {memory})"
+                ),
+                (Some(parse_duration), None) => {
+                    format!("(loaded in {load_duration:.2?}, parsed in {parse_duration:.2?})")
+                }
+                (None, _) => format!("(loaded in {load_duration:.2?})"),
             };
-            writeln!(f, "A : {solution_str}")?
+            let timing = timing.dimmed();
+            writeln!(f, "Input {timing}")?
         }
-        if self.run_b {
-            let solution_str = match self.result_b.as_ref() {
-                Some(s) => s.to_string().bold().cyan(),
-                None => "TODO".to_string().dimmed(),
+        for part in [Part::A, Part::B] {
+            let Some(outcome) = self.outcome(part) else {
+                continue;
+            };
+            // Blocks go on their own lines below the part, indented
+            let (answer, block) = match &outcome.result {
+                Ok(Answer::Block(block)) => (String::new(), Some(block)),
+                Ok(answer) => (format!("{answer} "), None),
+                Err(_) => (String::new(), None),
+            };
+            let (solution_str, expected_block) = match (&outcome.result, &outcome.check) {
+                (Ok(Answer::NotImplemented), _) => ("TODO".to_string().dimmed(), None),
+                (Ok(_), AnswerCheck::Correct) => (format!("{answer}✓").bold().green(), None),
+                (Ok(_), AnswerCheck::Wrong(expected)) if expected.contains('\n') => {
+                    (format!("{answer}✗").bold().red(), Some(expected))
+                }
+                (Ok(_), AnswerCheck::Wrong(expected)) => (
+                    format!("{answer}✗ (expected {expected})").bold().red(),
+                    None,
+                ),
+                (Ok(_), AnswerCheck::Unverified) => {
+                    (answer.trim_end().to_string().bold().cyan(), None)
+                }
+                (Err(AocRunError::TimedOut(..)), _) => {
+                    ("TIMEOUT".to_string().bold().yellow(), None)
+                }
+                (Err(e), _) => (e.to_string().normal(), None),
             };
-            writeln!(f, "B : {solution_str}")?
+            let timing = match outcome.memory {
+                Some(memory) => format!("({:.2?}, {memory})", outcome.duration),
+                None => format!("({:.2?})", outcome.duration),
+            };
+            let timing = timing.dimmed();
+            writeln!(f, "{part} : {solution_str} {timing}")?;
+            if let Some(block) = block {
+                let block = indent(block);
+                match outcome.check {
+                    AnswerCheck::Correct => writeln!(f, "{}", block.green())?,
+                    AnswerCheck::Wrong(_) => writeln!(f, "{}", block.red())?,
+                    AnswerCheck::Unverified => writeln!(f, "{}", block.cyan())?,
+                }
+            }
+            if let Some(expected) = expected_block {
+                writeln!(
+                    f,
+                    "{}",
+                    format!("  expected\n{}", indent(expected)).dimmed()
+                )?;
+            }
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn times_out_slow_parts() {
+        let timeout = Some(Duration::from_millis(50));
+        let result = attempt_within::<()>(timeout, Context::default(), |ctx| loop {
+            ctx.check_cancelled()?;
+            thread::sleep(Duration::from_millis(1));
+        });
+        assert!(matches!(result, Err(Failure::TimedOut(_))));
+
+        // Plenty of time, so that these can't time out on a busy machine
+        let timeout = Some(Duration::from_secs(10));
+        let result = attempt_within(timeout, Context::default(), |_ctx| Ok(42));
+        assert!(matches!(result, Ok(42)));
+        let result = attempt_within::<()>(timeout, Context::default(), |_ctx| panic!("oops"));
+        assert!(matches!(result, Err(Failure::Panic(msg)) if msg.starts_with("oops")));
+    }
+
+    fn parse(codes: &str) -> Vec<(String, Vec<Part>)> {
+        RunCode::parse_codes(codes, 2020)
+            .unwrap()
+            .into_iter()
+            .map(|run_code| (run_code.label(), run_code.parts().collect()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_run_codes() {
+        let both = vec![Part::A, Part::B];
+        assert_eq!(
+            parse("1-3"),
+            vec![
+                ("1".to_string(), both.clone()),
+                ("2".to_string(), both.clone()),
+                ("3".to_string(), both.clone()),
+            ]
+        );
+        assert_eq!(
+            parse("3,7,12b"),
+            vec![
+                ("3".to_string(), both.clone()),
+                ("7".to_string(), both.clone()),
+                ("12".to_string(), vec![Part::B]),
+            ]
+        );
+        assert_eq!(
+            parse("e4a,e4b"),
+            vec![
+                ("e4".to_string(), vec![Part::A]),
+                ("e4".to_string(), vec![Part::B]),
+            ]
+        );
+        let all = parse("all");
+        assert_eq!(all.len(), 25);
+        assert_eq!(all[24], ("25".to_string(), both.clone()));
+
+        let named = RunCode::parse_codes("e13b:3", 2020).unwrap();
+        assert_eq!(named.len(), 1);
+        assert_eq!(named[0].label(), "e13:3");
+        assert_eq!(named[0].example_name(), Some("3"));
+        assert_eq!(named[0].parts().collect::<Vec<_>>(), vec![Part::B]);
+
+        let other_year = RunCode::parse_codes("2019/1a,2", 2020).unwrap();
+        assert_eq!(other_year[0].year(), 2019);
+        assert_eq!(other_year[0].label(), "2019/1");
+        assert_eq!(other_year[1].year(), 2020);
+
+        for bad in ["10-1", "e1-3:x", "4c", "1,,2"] {
+            assert!(
+                matches!(
+                    RunCode::parse_codes(bad, 2020),
+                    Err(AocRunError::BadRunCode(_))
+                ),
+                "{bad} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn checks_example_names() {
+        for name in ["large", "3", "two_parts", "part-b"] {
+            assert!(is_example_name(name), "{name} should be accepted");
+        }
+        for name in ["", "../x", "a/b", "two words", "x.txt", "é"] {
+            assert!(!is_example_name(name), "{name} should be rejected");
+        }
+    }
+
+    #[test]
+    fn lists_examples() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert!(list_examples(dir.path(), 13).is_empty());
+        let examples = dir.path().join("examples");
+        fs::create_dir_all(&examples).unwrap();
+        for file in [
+            "13-large.txt",
+            "13.txt",
+            "13-3.txt",
+            "130.txt",
+            "013.txt",
+            "13.md",
+            "13_x.txt",
+            "01.txt",
+        ] {
+            fs::write(examples.join(file), "").unwrap();
+        }
+        assert_eq!(
+            list_examples(dir.path(), 13),
+            vec![None, Some("3".to_string()), Some("large".to_string())]
+        );
+        assert_eq!(list_examples(dir.path(), 1), vec![None]);
+        assert!(list_examples(dir.path(), 3).is_empty());
+    }
+
+    #[test]
+    fn reports_stub_days_without_their_input() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let options = RunOptions {
+            data_dir: dir.path().to_path_buf(),
+            ..RunOptions::default()
+        };
+        let solution = RunCode::puzzle(2020, 25).run(&options).unwrap();
+        for part in [Part::A, Part::B] {
+            let outcome = solution.outcome(part).unwrap();
+            assert!(matches!(outcome.result, Ok(Answer::NotImplemented)));
+        }
+        // Implemented days still need their input
+        assert!(matches!(
+            RunCode::puzzle(2020, 1).run(&options),
+            Err(AocRunError::NoFile(_))
+        ));
+    }
+
+    #[test]
+    fn records_where_solutions_panic() {
+        install_quiet_panic_hook();
+        let result = attempt::<()>(|| panic!("oops"));
+        assert!(
+            matches!(result, Err(Failure::Panic(msg)) if msg.starts_with("oops at src/io.rs:"))
+        );
+        // Only panics in solutions are recorded
+        assert!(!SOLVING.get());
+    }
+}
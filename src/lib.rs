@@ -0,0 +1,25 @@
+// The runner and the registry of days, shared by the aoc_2020 binary and the benchmarks
+pub mod answers;
+pub mod bench;
+pub mod cli;
+pub mod context;
+pub mod days;
+pub mod fetch;
+pub mod io;
+pub mod memory;
+pub mod normalize;
+pub mod problem;
+pub mod puzzle;
+pub mod regression;
+pub mod report;
+pub mod scaffold;
+pub mod submit;
+pub mod summary;
+#[cfg(test)]
+mod test_server;
+pub mod watch;
+
+// The binary installs the counting allocator, and so must the library's own tests
+#[cfg(test)]
+#[global_allocator]
+static ALLOCATOR: memory::CountingAllocator = memory::CountingAllocator;
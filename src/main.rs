@@ -1,11 +1,19 @@
-mod days;
-mod io;
-mod problem;
-
+use aoc_2020::answers::ExpectedAnswers;
+use aoc_2020::cli::{Cli, Command};
+use aoc_2020::fetch::{FetchOutcome, Fetcher};
+use aoc_2020::io::{AocRunError, PartOutcome, RunCode, RunOptions};
+use aoc_2020::problem::{Answer, Part};
+use aoc_2020::puzzle::PuzzlePage;
+use aoc_2020::report::OutputFormat;
+use aoc_2020::submit::{Ledger, SubmitResponse, Verdict};
+use aoc_2020::summary::Summary;
+use aoc_2020::watch::{AnswerHistory, Watcher, CLEAR_SCREEN};
+use aoc_2020::{days, io, memory, report, scaffold, submit, watch};
 use colored::Colorize;
-use io::{AocRunError, RunCode};
-use problem::ProblemSolution;
-use std::{env, str::FromStr};
+use itertools::Itertools;
+use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
+use std::{env, fs, path::Path, process::ExitCode, time::Instant};
 
 fn print_solution(solution: &Result<RunCode<true>, AocRunError>) {
     match solution.as_ref() {
@@ -14,29 +22,508 @@ fn print_solution(solution: &Result<RunCode<true>, AocRunError>) {
     };
 }
 
-pub fn main() {
-    let args: Vec<_> = env::args().collect();
-    if args.len() > 2 {
+fn has_wrong_answer(solution: &Result<RunCode<true>, AocRunError>) -> bool {
+    solution.as_ref().is_ok_and(|sol| sol.has_wrong_answer())
+}
+
+// Reads every argument as a list of run codes, expanding examples as we go
+fn parse_run_codes(
+    args: &[String],
+    options: &RunOptions,
+) -> Result<Vec<RunCode<false>>, AocRunError> {
+    let mut run_codes = vec![];
+    for arg in args {
+        run_codes.extend(RunCode::parse_codes(arg, options.year)?);
+    }
+    Ok(run_codes
+        .into_iter()
+        .flat_map(|run_code| run_code.expand_examples(options))
+        .collect())
+}
+
+// Solves every run code on a pool of `options.jobs` threads, keeping them in order
+fn run_parallel(
+    run_codes: &[RunCode<false>],
+    options: &RunOptions,
+) -> Vec<Result<RunCode<true>, AocRunError>> {
+    let run_all = || {
+        run_codes
+            .par_iter()
+            .map(|run_code| run_code.run(options))
+            .collect()
+    };
+    match ThreadPoolBuilder::new().num_threads(options.jobs).build() {
+        Ok(pool) => pool.install(run_all),
+        // Fall back to the global pool
+        Err(_) => run_all(),
+    }
+}
+
+// Returns whether any answer was wrong.
+// When watching, each answer is compared with the one from the previous run.
+fn run(
+    run_codes: &[RunCode<false>],
+    options: &RunOptions,
+    format: OutputFormat,
+    mut history: Option<&mut AnswerHistory>,
+) -> bool {
+    let show_summary = run_codes.len() > 1 && format == OutputFormat::Text;
+    let mut any_wrong = false;
+    let mut summary = Summary::default();
+    let mut records = vec![];
+    let start = Instant::now();
+    // Solved up front when running in parallel, otherwise each day is solved as it is printed
+    let mut solutions = (options.jobs > 1)
+        .then(|| run_parallel(run_codes, options))
+        .map(Vec::into_iter);
+    for run_code in run_codes.iter() {
+        if format == OutputFormat::Text {
+            if show_summary {
+                println!("{}", run_code.description());
+            } else if let Some(name) = run_code.example_name() {
+                println!("{}", format!("Example {name}").bold());
+            }
+        }
+        let solution = match solutions.as_mut().and_then(Iterator::next) {
+            Some(solution) => solution,
+            None => run_code.run(options),
+        };
+        match format {
+            OutputFormat::Text => {
+                print_solution(&solution);
+                if let (Some(history), Ok(sol)) = (history.as_deref_mut(), &solution) {
+                    watch::print_changes(&history.changes(run_code.label(), sol));
+                }
+            }
+            OutputFormat::Json | OutputFormat::Csv => {
+                records.extend(report::records(run_code, &solution, options))
+            }
+        }
+        summary.record(run_code, &solution);
+        any_wrong |= has_wrong_answer(&solution);
+    }
+    let written = match format {
+        OutputFormat::Text => Ok(()),
+        OutputFormat::Json => report::write_json(&records),
+        OutputFormat::Csv => report::write_csv(&records),
+    };
+    if let Err(e) = written {
+        eprintln!("Could not write results: {e}");
+    }
+    summary.set_wall_clock(start.elapsed(), options.jobs);
+    if show_summary {
+        println!();
+        print!("{summary}");
+    }
+    any_wrong
+}
+
+// Runs the run codes again whenever a file they read changes, until interrupted
+fn watch(run_codes: Vec<RunCode<false>>, options: &RunOptions, format: OutputFormat) {
+    let mut paths = vec![];
+    for run_code in run_codes.iter() {
+        match run_code.watched_paths(options) {
+            Some(run_code_paths) => paths.extend(run_code_paths),
+            None => {
+                print_usage_error("Can't watch an input read from stdin");
+                return;
+            }
+        }
+    }
+    let mut watcher = Watcher::new(paths);
+    let mut history = AnswerHistory::default();
+    loop {
+        if format == OutputFormat::Text {
+            print!("{CLEAR_SCREEN}");
+        }
+        run(&run_codes, options, format, Some(&mut history));
+        if format == OutputFormat::Text {
+            println!("{}", "Watching for changes, press Ctrl-C to stop".dimmed());
+        }
+        watcher.wait_for_change();
+    }
+}
+
+fn bench(run_codes: Vec<RunCode<false>>, options: &RunOptions, iterations: usize) {
+    for run_code in run_codes {
+        println!("{}", run_code.description());
+        match run_code.bench(options, iterations) {
+            Ok(report) => print!("{report}"),
+            Err(e) => println!("{e}"),
+        }
+    }
+}
+
+// Downloads the puzzle input for every day named by the run codes.
+// Returns whether every download succeeded.
+fn fetch(run_codes: Vec<RunCode<false>>, options: &RunOptions) -> bool {
+    let fetcher = match Fetcher::for_site(&options.data_dir) {
+        Ok(fetcher) => fetcher,
+        Err(e) => {
+            println!("{e}");
+            return false;
+        }
+    };
+    let mut all_fetched = true;
+    for run_code in run_codes
+        .into_iter()
+        .unique_by(|run_code| (run_code.year(), run_code.problem()))
+    {
+        let path = run_code.input_path(options);
+        let description = run_code.description();
+        match fetcher.fetch_input(run_code.year(), run_code.problem(), &path) {
+            Ok(FetchOutcome::Downloaded) => {
+                println!("{description}: saved to {}", path.display())
+            }
+            Ok(FetchOutcome::Cached) => {
+                let message = format!("{description}: already saved to {}", path.display());
+                println!("{}", message.dimmed())
+            }
+            Err(e) => {
+                println!("{description}: {e}");
+                all_fetched = false;
+            }
+        }
+    }
+    all_fetched
+}
+
+// Parses a run code naming the puzzle input for a single day
+fn parse_single_code(code: &str, options: &RunOptions) -> Result<RunCode<false>, AocRunError> {
+    let mut run_codes = RunCode::parse_codes(code, options.year)?;
+    if run_codes.len() == 1 && run_codes[0].is_puzzle_input() {
+        Ok(run_codes.remove(0))
+    } else {
+        Err(AocRunError::BadRunCode(code.to_string()))
+    }
+}
+
+// Prints a puzzle description, optionally saving one of its code blocks as an example.
+// Returns whether everything succeeded.
+fn puzzle(
+    run_code: RunCode<false>,
+    save: Option<(usize, Option<String>)>,
+    options: &RunOptions,
+) -> bool {
+    let (year, problem) = (run_code.year(), run_code.problem());
+    let page = match Fetcher::for_site(&options.data_dir)
+        .and_then(|fetcher| fetcher.fetch_puzzle(year, problem))
+    {
+        Ok(page) => PuzzlePage::parse(&page),
+        Err(e) => {
+            println!("{e}");
+            return false;
+        }
+    };
+    let blocks = page.example_blocks();
+    // The last emphasised answer in each part is usually the example's answer
+    let answers: Vec<_> = [Part::A, Part::B]
+        .into_iter()
+        .filter_map(|part| Some((part, page.example_answers(part).pop()?)))
+        .collect();
+
+    let Some((block, name)) = save else {
+        print!("{}", page.to_markdown());
+        println!();
+        for (i, block) in blocks.iter().enumerate() {
+            let first_line = block.lines().next().unwrap_or("");
+            let lines = block.lines().count();
+            let heading = format!("Block {}", i + 1).bold();
+            println!("{heading}: {lines} lines, starting {first_line}");
+        }
+        for (part, answer) in answers.iter() {
+            println!("{} {answer}", format!("Example answer {part}:").bold());
+        }
+        if !blocks.is_empty() {
+            let hint = "Save a block as an example with puzzle <day> <block> [<name>]";
+            println!("{}", hint.dimmed());
+        }
+        return true;
+    };
+
+    let Some(contents) = blocks.get(block - 1) else {
+        print_usage_error(&format!("The puzzle only has {} code blocks", blocks.len()));
+        return false;
+    };
+    let example = RunCode::example(year, problem, name);
+    let input_path = example.input_path(options);
+    if input_path.exists() {
+        print_usage_error(&format!("{} already exists", input_path.display()));
+        return false;
+    }
+    let saved = fs::create_dir_all(input_path.parent().unwrap())
+        .and_then(|_| fs::write(&input_path, contents));
+    if let Err(e) = saved {
+        print_usage_error(&format!("Could not write {}: {e}", input_path.display()));
+        return false;
+    }
+    println!("Saved block {block} to {}", input_path.display());
+
+    // Answers are only a guess, so never replace ones that are already there
+    let answers_path = example.answers_path(options);
+    if answers.is_empty() || answers_path.exists() {
+        return true;
+    }
+    let mut expected = ExpectedAnswers::default();
+    for (part, answer) in answers {
+        expected.set(part, answer);
+    }
+    match expected.save(&answers_path) {
+        Ok(()) => {
+            let message = format!(
+                "Saved candidate answers to {}, check that they match the example",
+                answers_path.display()
+            );
+            println!("{}", message.yellow());
+            true
+        }
+        Err(e) => {
+            print_usage_error(&format!("Could not write {}: {e}", answers_path.display()));
+            false
+        }
+    }
+}
+
+// Solves a single part and submits the answer.
+// Returns whether the answer was accepted.
+fn submit(run_code: RunCode<false>, options: &RunOptions) -> bool {
+    let Some(part) = run_code.parts().exactly_one().ok() else {
+        println!("{}", AocRunError::BadRunCode(run_code.label()));
+        return false;
+    };
+    let (year, problem) = (run_code.year(), run_code.problem());
+    let answers_path = run_code.answers_path(options);
+    let solution = run_code.run(options);
+    print_solution(&solution);
+    let answer = solution.ok().and_then(|sol| match sol.outcome(part) {
+        Some(PartOutcome {
+            result: Ok(answer), ..
+        }) => Some(answer.clone()),
+        _ => None,
+    });
+    let answer = match answer {
+        Some(Answer::Integer(n)) => n.to_string(),
+        Some(Answer::Text(text)) => text.trim().to_string(),
+        Some(Answer::Block(_)) => {
+            let message = "Multi-line answers have to be read and submitted by hand";
+            println!("{}", message.bold().red());
+            return false;
+        }
+        Some(Answer::NotImplemented) | None => {
+            println!("{}", "No answer to submit".bold().red());
+            return false;
+        }
+    };
+
+    let mut ledger = match Ledger::load(Ledger::path(&options.data_dir, year)) {
+        Ok(ledger) => ledger,
+        Err(e) => {
+            println!("{e}");
+            return false;
+        }
+    };
+    if let Some(warning) = ledger.bounds_warning(problem, part, &answer) {
+        println!("{}", format!("Warning: {warning}").yellow());
+    }
+    let fetcher = match Fetcher::for_site(&options.data_dir) {
+        Ok(fetcher) => fetcher,
+        Err(e) => {
+            println!("{e}");
+            return false;
+        }
+    };
+    let response = match submit::submit_answer(&fetcher, &mut ledger, year, problem, part, &answer)
+    {
+        Ok(response) => response,
+        Err(e) => {
+            println!("{e}");
+            return false;
+        }
+    };
+    println!("{response}");
+    if response != SubmitResponse::Verdict(Verdict::Correct) {
+        return false;
+    }
+    // Remember the answer so that future runs are checked against it
+    let saved = ExpectedAnswers::load(&answers_path.to_string_lossy()).and_then(|mut expected| {
+        expected.set(part, answer);
+        expected
+            .save(&answers_path)
+            .map_err(|_e| AocRunError::NoFile(answers_path.display().to_string()))
+    });
+    if let Err(e) = saved {
+        println!("{e}");
+    }
+    true
+}
+
+// Prints every registered day, optionally only those with the given tag
+fn list(tag: Option<&str>) {
+    let days: Vec<_> = days::DAYS
+        .iter()
+        .filter(|day| tag.is_none_or(|tag| day.tags.contains(&tag)))
+        .collect();
+    let title_width = days
+        .iter()
+        .map(|day| day.title.len())
+        .max()
+        .unwrap_or(0)
+        .max(5);
+    println!("Year | Day | {:<title_width$} | A | B | Tags", "Title");
+    println!("-----+-----+-{:-<title_width$}-+---+---+-----", "");
+    for day in days {
+        let implemented = |part| {
+            if day.is_implemented(part) {
+                "✓".green()
+            } else {
+                "-".dimmed()
+            }
+        };
         println!(
-            "{}",
-            "Please provide at most 1 argument".to_string().bold().red()
+            "{} | {:>3} | {:<title_width$} | {} | {} | {}",
+            day.year,
+            day.problem,
+            day.title,
+            implemented(Part::A),
+            implemented(Part::B),
+            day.tags.join(", ")
         );
-        return;
     }
+}
 
-    if args.len() == 1 {
-        for i in 1..=25 {
-            println!("Day {}", i);
-            let run_code = RunCode::init_run_all(i);
-            let solution = run_code.run();
-            print_solution(&solution);
-            if solution.is_err() {
-                break;
+fn print_usage_error(message: &str) {
+    println!("{}", message.bold().red());
+}
+
+// Only counts allocations once --memory enables it
+#[global_allocator]
+static ALLOCATOR: memory::CountingAllocator = memory::CountingAllocator;
+
+pub fn main() -> ExitCode {
+    let Cli {
+        command,
+        options,
+        format,
+        watch: watch_files,
+    } = match Cli::parse(env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(message) => {
+            print_usage_error(&message);
+            return ExitCode::FAILURE;
+        }
+    };
+    io::install_quiet_panic_hook();
+    if options.memory {
+        memory::enable();
+    }
+    // Structured output is for scripts, which don't want escape codes
+    if format != OutputFormat::Text {
+        colored::control::set_override(false);
+    }
+    let (codes, bench_iterations) = match command {
+        Command::Run(codes) => (codes, None),
+        Command::Bench(codes, iterations) => (vec![codes], Some(iterations)),
+        Command::Submit(code) => {
+            if options.input.is_some() {
+                print_usage_error("Answers for an overridden input can't be submitted");
+                return ExitCode::FAILURE;
+            }
+            let succeeded = match parse_single_code(&code, &options) {
+                Ok(run_code) => submit(run_code, &options),
+                Err(e) => {
+                    println!("{e}");
+                    false
+                }
+            };
+            return if succeeded {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            };
+        }
+        Command::Puzzle(code, save) => {
+            let succeeded = match parse_single_code(&code, &options) {
+                Ok(run_code) => puzzle(run_code, save, &options),
+                Err(e) => {
+                    println!("{e}");
+                    false
+                }
+            };
+            return if succeeded {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            };
+        }
+        Command::List(tag) => {
+            list(tag.as_deref());
+            return ExitCode::SUCCESS;
+        }
+        Command::New(code) => {
+            let created = parse_single_code(&code, &options)
+                .map_err(|e| e.to_string())
+                .and_then(|run_code| {
+                    let days_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/days");
+                    scaffold::new_day(&days_dir, run_code.year(), run_code.problem())
+                        .map_err(|e| e.to_string())
+                });
+            return match created {
+                Ok(path) => {
+                    println!("Created {}", path.display());
+                    ExitCode::SUCCESS
+                }
+                Err(message) => {
+                    println!("{message}");
+                    ExitCode::FAILURE
+                }
+            };
+        }
+        Command::Fetch(codes) => {
+            let mut run_codes = vec![];
+            for code in codes {
+                match RunCode::parse_codes(&code, options.year) {
+                    // Only puzzle inputs can be downloaded
+                    Ok(parsed) if parsed.iter().all(RunCode::is_puzzle_input) => {
+                        run_codes.extend(parsed)
+                    }
+                    Ok(_) => {
+                        println!("{}", AocRunError::BadRunCode(code));
+                        return ExitCode::FAILURE;
+                    }
+                    Err(e) => {
+                        println!("{e}");
+                        return ExitCode::FAILURE;
+                    }
+                }
             }
+            return if fetch(run_codes, &options) {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            };
         }
+    };
+    let run_codes = match parse_run_codes(&codes, &options) {
+        Ok(run_codes) => run_codes,
+        Err(e) => {
+            println!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let any_wrong = match bench_iterations {
+        Some(iterations) => {
+            bench(run_codes, &options, iterations);
+            false
+        }
+        None if watch_files => {
+            watch(run_codes, &options, format);
+            true
+        }
+        None => run(&run_codes, &options, format, None),
+    };
+    if any_wrong {
+        ExitCode::FAILURE
     } else {
-        let run_code = RunCode::from_str(&args[1]);
-        let solution = run_code.and_then(|rc| rc.run());
-        print_solution(&solution);
+        ExitCode::SUCCESS
     }
 }
@@ -0,0 +1,195 @@
+// Support for --memory, which counts what each part allocates.
+// The counting allocator is always installed but does nothing until enabled,
+// so runs without --memory only pay for checking a flag.
+use serde::Serialize;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+// Counts are kept per thread, so that parts running at once with --jobs don't mix.
+// Allocations on threads that a solution starts itself are not counted.
+#[derive(Clone, Copy)]
+struct Counters {
+    // Can go below zero when memory from before the measurement is freed
+    current: i64,
+    peak: i64,
+    allocations: u64,
+}
+
+thread_local! {
+    static COUNTERS: Cell<Counters> = const {
+        Cell::new(Counters {
+            current: 0,
+            peak: 0,
+            allocations: 0,
+        })
+    };
+}
+
+fn record(allocated: usize, freed: usize, allocations: u64) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    // The thread local is gone while a thread is shutting down
+    let _ = COUNTERS.try_with(|counters| {
+        let mut c = counters.get();
+        c.current += allocated as i64 - freed as i64;
+        c.peak = c.peak.max(c.current);
+        c.allocations += allocations;
+        counters.set(c);
+    });
+}
+
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(layout.size(), 0, 1);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(layout.size(), 0, 1);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(0, layout.size(), 0);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record(new_size, layout.size(), 1);
+        }
+        new_ptr
+    }
+}
+
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+// What a part allocated while it ran
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct MemoryStats {
+    // The most memory held at once, above what was held when the part started
+    pub peak_bytes: u64,
+    pub allocations: u64,
+}
+
+impl Display for MemoryStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+        let mut size = self.peak_bytes as f64;
+        let mut unit = 0;
+        while size >= 1024.0 && unit < UNITS.len() - 1 {
+            size /= 1024.0;
+            unit += 1;
+        }
+        let allocations = self.allocations;
+        if unit == 0 {
+            write!(f, "peak {size} B, {allocations} allocations")
+        } else {
+            write!(
+                f,
+                "peak {size:.1} {}, {allocations} allocations",
+                UNITS[unit]
+            )
+        }
+    }
+}
+
+// Runs f, counting what it allocates on this thread. Returns None for the stats
+// unless counting has been enabled.
+// Measurements can nest, e.g. when rayon runs another part on this thread while a
+// solution waits for its own parallel work, and the inner one counts toward the outer.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<MemoryStats>) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return (f(), None);
+    }
+    let outer = COUNTERS.with(|counters| {
+        counters.replace(Counters {
+            current: 0,
+            peak: 0,
+            allocations: 0,
+        })
+    });
+    let result = f();
+    let inner = COUNTERS.with(Cell::get);
+    COUNTERS.with(|counters| {
+        counters.set(Counters {
+            current: outer.current + inner.current,
+            peak: outer.peak.max(outer.current + inner.peak),
+            allocations: outer.allocations + inner.allocations,
+        })
+    });
+    let stats = MemoryStats {
+        peak_bytes: inner.peak as u64,
+        allocations: inner.allocations,
+    };
+    (result, Some(stats))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_allocations() {
+        enable();
+        let (total, stats) = measure(|| {
+            let numbers: Vec<u64> = Vec::with_capacity(1000);
+            let small = Box::new([1u8; 10]);
+            numbers.capacity() + small.len()
+        });
+        assert_eq!(total, 1010);
+        let stats = stats.unwrap();
+        assert_eq!(stats.allocations, 2);
+        assert_eq!(stats.peak_bytes, 8010);
+
+        let (_, stats) = measure(|| ());
+        assert_eq!(stats.unwrap().allocations, 0);
+    }
+
+    #[test]
+    fn counts_nested_measurements_in_the_outer_one() {
+        enable();
+        let (inner, outer) = measure(|| {
+            let before = Box::new([1u8; 100]);
+            let (_, inner) = measure(|| Box::new([1u8; 1000]));
+            drop(before);
+            inner
+        });
+        let inner = inner.unwrap();
+        assert_eq!(inner.allocations, 1);
+        assert_eq!(inner.peak_bytes, 1000);
+        let outer = outer.unwrap();
+        assert_eq!(outer.allocations, 2);
+        assert_eq!(outer.peak_bytes, 1100);
+    }
+
+    #[test]
+    fn formats_sizes() {
+        let stats = |peak_bytes| MemoryStats {
+            peak_bytes,
+            allocations: 3,
+        };
+        assert_eq!(stats(512).to_string(), "peak 512 B, 3 allocations");
+        assert_eq!(stats(1536).to_string(), "peak 1.5 KiB, 3 allocations");
+        assert_eq!(
+            stats(40 * 1024 * 1024).to_string(),
+            "peak 40.0 MiB, 3 allocations"
+        );
+    }
+}
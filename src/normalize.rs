@@ -0,0 +1,139 @@
+// Tidies up an input before a solution sees it, so that an input saved on Windows or
+// by an editor that adds or drops newlines still parses
+use crate::days::{self, DayInfo};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FinalNewline {
+    Keep,
+    // Exactly one, dropping any trailing blank lines
+    Single,
+    Strip,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Normalization {
+    pub crlf_to_lf: bool,
+    // Trailing whitespace on every line, which also turns lines of spaces into blank lines
+    pub trim_trailing_whitespace: bool,
+    pub final_newline: FinalNewline,
+}
+
+impl Default for Normalization {
+    fn default() -> Self {
+        Self {
+            crlf_to_lf: true,
+            trim_trailing_whitespace: true,
+            final_newline: FinalNewline::Single,
+        }
+    }
+}
+
+impl Normalization {
+    pub const NONE: Self = Self {
+        crlf_to_lf: false,
+        trim_trailing_whitespace: false,
+        final_newline: FinalNewline::Keep,
+    };
+
+    // Reads a comma separated list of steps, e.g. "crlf,newline", or "none"
+    pub fn parse(steps: &str) -> Option<Self> {
+        let mut normalization = Self::NONE;
+        for step in steps.split(',') {
+            match step.trim() {
+                "none" => {}
+                "crlf" => normalization.crlf_to_lf = true,
+                "trim" => normalization.trim_trailing_whitespace = true,
+                "newline" => normalization.final_newline = FinalNewline::Single,
+                "no-newline" => normalization.final_newline = FinalNewline::Strip,
+                _ => return None,
+            }
+        }
+        Some(normalization)
+    }
+
+    // Days that opt out with raw_input in the register_days! table see their input as is
+    pub fn for_day(self, year: u16, problem: usize) -> Self {
+        self.for_day_info(days::find(year, problem))
+    }
+
+    fn for_day_info(self, day: Option<&DayInfo>) -> Self {
+        match day {
+            Some(day) if day.raw_input => Self::NONE,
+            _ => self,
+        }
+    }
+
+    pub fn apply(&self, input: String) -> String {
+        let mut input = if self.crlf_to_lf {
+            input.replace("\r\n", "\n")
+        } else {
+            input
+        };
+        if self.trim_trailing_whitespace {
+            input = input
+                .split('\n')
+                .map(str::trim_end)
+                .collect::<Vec<_>>()
+                .join("\n");
+        }
+        match self.final_newline {
+            FinalNewline::Keep => {}
+            FinalNewline::Single => {
+                input.truncate(input.trim_end_matches('\n').len());
+                if !input.is_empty() {
+                    input.push('\n');
+                }
+            }
+            FinalNewline::Strip => input.truncate(input.trim_end_matches('\n').len()),
+        }
+        input
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_inputs() {
+        let normalize =
+            |normalization: Normalization, input: &str| normalization.apply(input.to_string());
+        let default = Normalization::default();
+        assert_eq!(normalize(default, "1721\r\n979\r\n"), "1721\n979\n");
+        assert_eq!(normalize(default, "1721\n979"), "1721\n979\n");
+        assert_eq!(normalize(default, "1721\n979\n\n  \n"), "1721\n979\n");
+        assert_eq!(
+            normalize(default, "ecl:gry  \n\nbyr:1937\n"),
+            "ecl:gry\n\nbyr:1937\n"
+        );
+        assert_eq!(normalize(default, ""), "");
+
+        let crlf_only = Normalization::parse("crlf").unwrap();
+        assert_eq!(normalize(crlf_only, "a \r\nb\n\n"), "a \nb\n\n");
+        let stripped = Normalization::parse("trim,no-newline").unwrap();
+        assert_eq!(normalize(stripped, "a \r\nb\n\n"), "a\nb");
+        assert_eq!(normalize(Normalization::NONE, "a \r\n"), "a \r\n");
+
+        assert_eq!(Normalization::parse("none"), Some(Normalization::NONE));
+        assert_eq!(
+            Normalization::parse("crlf,trim,newline"),
+            Some(Normalization::default())
+        );
+        assert_eq!(Normalization::parse("tabs"), None);
+    }
+
+    #[test]
+    fn leaves_raw_input_days_alone() {
+        let normalization = Normalization::parse("crlf,trim").unwrap();
+        let raw_day = DayInfo::stub(2020, 30, true);
+        assert_eq!(
+            normalization.for_day_info(Some(&raw_day)),
+            Normalization::NONE
+        );
+        let day = DayInfo::stub(2020, 30, false);
+        assert_eq!(normalization.for_day_info(Some(&day)), normalization);
+        // Unregistered days are normalized too
+        assert_eq!(normalization.for_day(2020, 30), normalization);
+        assert_eq!(normalization.for_day(2020, 1), normalization);
+    }
+}
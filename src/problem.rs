@@ -1,10 +1,291 @@
-pub trait ProblemSolution {
+use std::any::Any;
+use std::fmt::Display;
+
+use aoc_parse::ParseError;
+
+use crate::context::Context;
+use serde::{Serialize, Serializer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum Part {
+    A,
+    B,
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::A => write!(f, "A"),
+            Part::B => write!(f, "B"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum SolveError {
+    Parse(String),
+    NoAnswer,
+    UnsupportedInput(String),
+    // The day has no default for a parameter it asked for
+    MissingParam(String),
+    // The solution noticed that it had been asked to stop, see Context::check_cancelled
+    Cancelled,
+}
+
+impl From<ParseError> for SolveError {
+    fn from(value: ParseError) -> Self {
+        SolveError::Parse(value.to_string())
+    }
+}
+
+// What a solution gives for a part
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Integer(i128),
+    Text(String),
+    // Several lines, such as letters drawn in ASCII art
+    Block(String),
+    NotImplemented,
+}
+
+impl Answer {
+    pub fn is_implemented(&self) -> bool {
+        !matches!(self, Answer::NotImplemented)
+    }
+
+    // Compares with an answer read from a file, which may be formatted differently,
+    // e.g. an integer with leading zeros or a block with trailing spaces
+    pub fn matches(&self, expected: &str) -> bool {
+        match self {
+            Answer::Integer(n) => expected.trim().parse::<i128>() == Ok(*n),
+            Answer::Text(text) => expected.trim() == text.trim(),
+            Answer::Block(block) => block_lines(expected) == block_lines(block),
+            Answer::NotImplemented => false,
+        }
+    }
+}
+
+// The lines of a block without trailing whitespace or blank lines at either end
+fn block_lines(block: &str) -> Vec<&str> {
+    let lines: Vec<_> = block.lines().map(str::trim_end).collect();
+    let start = lines.iter().position(|line| !line.is_empty());
+    let end = lines.iter().rposition(|line| !line.is_empty());
+    match (start, end) {
+        (Some(start), Some(end)) => lines[start..=end].to_vec(),
+        _ => vec![],
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Integer(n) => write!(f, "{n}"),
+            Answer::Text(text) | Answer::Block(text) => write!(f, "{text}"),
+            Answer::NotImplemented => write!(f, "not implemented"),
+        }
+    }
+}
+
+// Integers stay numbers in JSON, and a missing answer is null
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Answer::Integer(n) => serializer.serialize_i128(*n),
+            Answer::Text(text) | Answer::Block(text) => serializer.serialize_str(text),
+            Answer::NotImplemented => serializer.serialize_none(),
+        }
+    }
+}
+
+macro_rules! integer_answers {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer::Integer(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+integer_answers!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        if value.trim_end().contains('\n') {
+            Answer::Block(value.trim_end().to_string())
+        } else {
+            Answer::Text(value)
+        }
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::from(value.to_string())
+    }
+}
+
+pub type SolveResult = Result<Answer, SolveError>;
+
+// Sync so that both parts can be solved at once when running with --jobs,
+// and Send so that each part can run on its own thread with --timeout
+pub trait ProblemSolution: Send + Sync {
     #[allow(unused_variables)]
-    fn solve_a(&self, input: &str) -> Option<String> {
-        None
+    fn solve_a(&self, input: &str, ctx: &Context) -> SolveResult {
+        Ok(Answer::NotImplemented)
     }
     #[allow(unused_variables)]
-    fn solve_b(&self, input: &str) -> Option<String> {
+    fn solve_b(&self, input: &str, ctx: &Context) -> SolveResult {
+        Ok(Answer::NotImplemented)
+    }
+
+    fn solve(&self, part: Part, input: &str, ctx: &Context) -> SolveResult {
+        match part {
+            Part::A => self.solve_a(input, ctx),
+            Part::B => self.solve_b(input, ctx),
+        }
+    }
+
+    // Solutions that parse their input once, ahead of both parts, return it here.
+    // None means that each part parses the input itself.
+    #[allow(unused_variables)]
+    fn parse_once(&self, input: &str, ctx: &Context) -> Option<Result<ParsedInput, SolveError>> {
         None
     }
+
+    // Only called with the input returned by parse_once
+    #[allow(unused_variables)]
+    fn solve_parsed(&self, part: Part, parsed: &ParsedInput, ctx: &Context) -> SolveResult {
+        Ok(Answer::NotImplemented)
+    }
+}
+
+// The input of a ParsedSolution, with its type hidden so that it can pass through the runner
+pub struct ParsedInput(Box<dyn Any + Send + Sync>);
+
+// A solution that parses its input once and solves both parts from the result
+pub trait ParsedSolution: Send + Sync {
+    type Parsed: Send + Sync + 'static;
+
+    fn parse(&self, input: &str, ctx: &Context) -> Result<Self::Parsed, SolveError>;
+
+    #[allow(unused_variables)]
+    fn part_a(&self, parsed: &Self::Parsed, ctx: &Context) -> SolveResult {
+        Ok(Answer::NotImplemented)
+    }
+    #[allow(unused_variables)]
+    fn part_b(&self, parsed: &Self::Parsed, ctx: &Context) -> SolveResult {
+        Ok(Answer::NotImplemented)
+    }
+}
+
+impl<T: ParsedSolution> ProblemSolution for T {
+    fn solve_a(&self, input: &str, ctx: &Context) -> SolveResult {
+        self.part_a(&self.parse(input, ctx)?, ctx)
+    }
+
+    fn solve_b(&self, input: &str, ctx: &Context) -> SolveResult {
+        self.part_b(&self.parse(input, ctx)?, ctx)
+    }
+
+    fn parse_once(&self, input: &str, ctx: &Context) -> Option<Result<ParsedInput, SolveError>> {
+        Some(
+            self.parse(input, ctx)
+                .map(|parsed| ParsedInput(Box::new(parsed))),
+        )
+    }
+
+    fn solve_parsed(&self, part: Part, parsed: &ParsedInput, ctx: &Context) -> SolveResult {
+        let parsed = parsed
+            .0
+            .downcast_ref::<T::Parsed>()
+            .expect("input was parsed by a different solution");
+        match part {
+            Part::A => self.part_a(parsed, ctx),
+            Part::B => self.part_b(parsed, ctx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_answers() {
+        assert_eq!(Answer::from(514579usize), Answer::Integer(514579));
+        assert_eq!(Answer::from(-3i64), Answer::Integer(-3));
+        assert_eq!(Answer::from("abc"), Answer::Text("abc".to_string()));
+        assert_eq!(
+            Answer::from("#..#\n####\n".to_string()),
+            Answer::Block("#..#\n####".to_string())
+        );
+    }
+
+    #[test]
+    fn matches_expected_answers() {
+        assert!(Answer::Integer(42).matches("42"));
+        assert!(Answer::Integer(42).matches(" 042\n"));
+        assert!(!Answer::Integer(42).matches("43"));
+        assert!(!Answer::Integer(42).matches("forty two"));
+        assert!(Answer::Text("mxmxvkd,sqjhc".to_string()).matches("mxmxvkd,sqjhc "));
+        assert!(!Answer::Text("abc".to_string()).matches("ABC"));
+        let block = Answer::Block("#..#\n####".to_string());
+        assert!(block.matches("\n#..#  \n####\n\n"));
+        assert!(!block.matches("#..#"));
+        assert!(!Answer::NotImplemented.matches(""));
+    }
+
+    struct Lengths {}
+
+    impl ParsedSolution for Lengths {
+        type Parsed = Vec<usize>;
+
+        fn parse(&self, input: &str, _ctx: &Context) -> Result<Self::Parsed, SolveError> {
+            if input.is_empty() {
+                return Err(SolveError::Parse("empty input".to_string()));
+            }
+            Ok(input.lines().map(str::len).collect())
+        }
+
+        fn part_a(&self, parsed: &Self::Parsed, _ctx: &Context) -> SolveResult {
+            Ok(parsed.iter().sum::<usize>().into())
+        }
+    }
+
+    #[test]
+    fn bridges_parsed_solutions() {
+        let solution: Box<dyn ProblemSolution> = Box::new(Lengths {});
+        let ctx = Context::default();
+        assert_eq!(
+            solution.solve(Part::A, "ab\nc", &ctx).unwrap(),
+            Answer::Integer(3)
+        );
+        assert_eq!(
+            solution.solve(Part::B, "ab\nc", &ctx).unwrap(),
+            Answer::NotImplemented
+        );
+        assert!(solution.solve(Part::A, "", &ctx).is_err());
+
+        let parsed = solution.parse_once("abc\nd", &ctx).unwrap().unwrap();
+        assert_eq!(
+            solution.solve_parsed(Part::A, &parsed, &ctx).unwrap(),
+            Answer::Integer(4)
+        );
+        assert!(solution.parse_once("", &ctx).unwrap().is_err());
+    }
+
+    #[test]
+    fn serializes_answers() {
+        let json = serde_json::to_string(&[
+            Answer::Integer(7),
+            Answer::Text("abc".to_string()),
+            Answer::Block("#.\n.#".to_string()),
+            Answer::NotImplemented,
+        ])
+        .unwrap();
+        assert_eq!(json, r##"[7,"abc","#.\n.#",null]"##);
+    }
 }
@@ -0,0 +1,294 @@
+// Reads puzzle descriptions downloaded from the Advent of Code website.
+// The pages are simple enough that a small tokenizer does the job: only the
+// <article> elements, one per part, hold the description.
+use crate::problem::Part;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Open(String),
+    Close(String),
+    Text(String),
+}
+
+fn tokenize(html: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            tokens.push(Token::Text(decode_entities(rest)));
+            break;
+        };
+        if start > 0 {
+            tokens.push(Token::Text(decode_entities(&rest[..start])));
+        }
+        rest = &rest[start..];
+        // Comments may contain '>', so they need their own terminator
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.split_once("-->").map_or("", |(_, after)| after);
+            continue;
+        }
+        let Some(end) = rest.find('>') else {
+            tokens.push(Token::Text(decode_entities(rest)));
+            break;
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+        let name = |tag: &str| {
+            tag.split(|c: char| c.is_whitespace() || c == '/')
+                .next()
+                .unwrap_or("")
+                .to_ascii_lowercase()
+        };
+        if let Some(tag) = tag.strip_prefix('/') {
+            tokens.push(Token::Close(name(tag)));
+        } else if !tag.starts_with('!') {
+            let name = name(tag);
+            // Scripts aren't HTML, so skip straight to their closing tag
+            if name == "script" || name == "style" {
+                let close = format!("</{name}>");
+                rest = rest.split_once(&close).map_or("", |(_, after)| after);
+                continue;
+            }
+            tokens.push(Token::Open(name));
+        }
+    }
+    tokens
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest[1..].split_once(';').and_then(|(entity, _)| {
+            let c = match entity {
+                "lt" => '<',
+                "gt" => '>',
+                "amp" => '&',
+                "quot" => '"',
+                "apos" => '\'',
+                "nbsp" => ' ',
+                _ => {
+                    let code = match entity.strip_prefix("#x") {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => entity.strip_prefix('#')?.parse().ok()?,
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((c, entity.len() + 2))
+        });
+        match entity {
+            Some((c, len)) => {
+                decoded.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+pub struct PuzzlePage {
+    // The tokens inside each <article>, the first for part A and the second for part B
+    articles: Vec<Vec<Token>>,
+}
+
+impl PuzzlePage {
+    pub fn parse(html: &str) -> Self {
+        let mut articles = vec![];
+        let mut current: Option<Vec<Token>> = None;
+        for token in tokenize(html) {
+            match (&token, current.as_mut()) {
+                (Token::Open(tag), None) if tag == "article" => current = Some(vec![]),
+                (Token::Close(tag), Some(_)) if tag == "article" => articles.extend(current.take()),
+                (_, Some(article)) => article.push(token),
+                (_, None) => {}
+            }
+        }
+        Self { articles }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        for article in self.articles.iter() {
+            let mut in_pre = false;
+            let mut in_code = false;
+            for token in article {
+                match token {
+                    Token::Open(tag) => match tag.as_str() {
+                        "h2" => out.push_str("## "),
+                        "pre" => {
+                            in_pre = true;
+                            out.push_str("```\n");
+                        }
+                        "code" if !in_pre => {
+                            in_code = true;
+                            out.push('`');
+                        }
+                        // Markdown can't emphasise inside code, so the emphasis is dropped
+                        "em" if !in_pre && !in_code => out.push_str("**"),
+                        "li" => out.push_str("- "),
+                        _ => {}
+                    },
+                    Token::Close(tag) => match tag.as_str() {
+                        "h2" | "p" => out.push_str("\n\n"),
+                        "pre" => {
+                            in_pre = false;
+                            if !out.ends_with('\n') {
+                                out.push('\n');
+                            }
+                            out.push_str("```\n\n");
+                        }
+                        "code" if !in_pre => {
+                            in_code = false;
+                            out.push('`');
+                        }
+                        "em" if !in_pre && !in_code => out.push_str("**"),
+                        "li" => out.push('\n'),
+                        "ul" => out.push('\n'),
+                        _ => {}
+                    },
+                    Token::Text(text) if in_pre => out.push_str(text),
+                    Token::Text(text) => {
+                        // Line breaks in the source are not line breaks on the page
+                        if out.ends_with('\n') {
+                            out.push_str(text.trim_start());
+                        } else {
+                            out.push_str(&text.replace('\n', " "));
+                        }
+                    }
+                }
+            }
+        }
+        out.trim_end().to_string() + "\n"
+    }
+
+    // The contents of every <pre><code> block, which is where examples are shown
+    pub fn example_blocks(&self) -> Vec<String> {
+        let mut blocks = vec![];
+        for article in self.articles.iter() {
+            let mut block: Option<String> = None;
+            let mut in_pre = false;
+            for token in article {
+                match token {
+                    Token::Open(tag) if tag == "pre" => in_pre = true,
+                    Token::Close(tag) if tag == "pre" => in_pre = false,
+                    Token::Open(tag) if tag == "code" && in_pre => block = Some(String::new()),
+                    Token::Close(tag) if tag == "code" => blocks.extend(block.take()),
+                    Token::Text(text) => {
+                        if let Some(block) = block.as_mut() {
+                            block.push_str(text)
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        blocks
+            .into_iter()
+            .filter(|block| !block.trim().is_empty())
+            .map(|block| block.trim_end().to_string() + "\n")
+            .collect()
+    }
+
+    // Emphasised code in the description of a part, e.g. <code><em>514579</em></code>.
+    // The example answer is usually the last one.
+    pub fn example_answers(&self, part: Part) -> Vec<String> {
+        let index = match part {
+            Part::A => 0,
+            Part::B => 1,
+        };
+        let Some(article) = self.articles.get(index) else {
+            return vec![];
+        };
+        let mut answers = vec![];
+        let mut in_pre = false;
+        let mut code: Option<String> = None;
+        let mut in_em = false;
+        for token in article {
+            match token {
+                Token::Open(tag) if tag == "pre" => in_pre = true,
+                Token::Close(tag) if tag == "pre" => in_pre = false,
+                Token::Open(tag) if tag == "code" && !in_pre => code = Some(String::new()),
+                Token::Close(tag) if tag == "code" => {
+                    answers.extend(code.take().filter(|answer| !answer.trim().is_empty()))
+                }
+                // Code inside an emphasised sentence is not an answer
+                Token::Open(tag) if tag == "em" => in_em = code.is_some(),
+                Token::Close(tag) if tag == "em" => in_em = false,
+                Token::Text(text) if in_em => {
+                    if let Some(code) = code.as_mut() {
+                        code.push_str(text)
+                    }
+                }
+                _ => {}
+            }
+        }
+        answers
+            .into_iter()
+            .map(|answer| answer.trim().to_string())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_1: &str = include_str!("../fixtures/puzzles/2020-01.html");
+    const DAY_13: &str = include_str!("../fixtures/puzzles/2020-13.html");
+
+    #[test]
+    fn finds_example_blocks() {
+        let page = PuzzlePage::parse(DAY_1);
+        assert_eq!(
+            page.example_blocks(),
+            vec!["1721\n979\n366\n299\n675\n1456\n".to_string()]
+        );
+
+        // Emphasis inside an example is dropped
+        let page = PuzzlePage::parse(DAY_13);
+        assert_eq!(
+            page.example_blocks(),
+            vec![
+                "939\n7,13,x,x,59,x,31,19\n".to_string(),
+                "time   bus 7   bus 13\n929      .       .\n930      .       .\n\
+                 944      .       .       .       D       .       .\n"
+                    .to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_example_answers() {
+        let page = PuzzlePage::parse(DAY_1);
+        assert_eq!(page.example_answers(Part::A), vec!["514579"]);
+        assert_eq!(page.example_answers(Part::B), vec!["241861950"]);
+
+        let page = PuzzlePage::parse(DAY_13);
+        assert_eq!(page.example_answers(Part::A), vec!["295"]);
+        // Part B has not been unlocked
+        assert!(page.example_answers(Part::B).is_empty());
+    }
+
+    #[test]
+    fn renders_markdown() {
+        let markdown = PuzzlePage::parse(DAY_1).to_markdown();
+        assert!(markdown.starts_with("## --- Day 1: Report Repair ---\n\n"));
+        assert!(markdown.contains("find the **two entries** that sum to `2020`"));
+        assert!(markdown.contains("```\n1721\n979\n366\n299\n675\n1456\n```\n"));
+        assert!(markdown.contains("- `1721` and `299` sum to `2020`\n"));
+        assert!(markdown.contains("- every other pair has a sum < or > `2020`\n"));
+        assert!(markdown.contains("the correct answer is `514579`."));
+        assert!(markdown.contains("## --- Part Two ---\n\n"));
+        assert!(!markdown.contains("Your puzzle answer"));
+        assert!(!markdown.contains("<code>"));
+        assert!(!markdown.contains("addEventListener"));
+    }
+}
@@ -0,0 +1,113 @@
+// Regression checks of every day and part against each input that has an answer file.
+// tests/regression.rs turns each case into its own test, so refactoring a day can be
+// checked with a plain `cargo test`.
+// Inputs or answers that are missing (e.g. data has not been downloaded) are skipped.
+use crate::answers::{AnswerCheck, ExpectedAnswers};
+use crate::days;
+use crate::io::{RunCode, RunOptions};
+use crate::problem::{Answer, Part};
+
+pub enum CaseOutcome {
+    Passed,
+    Skipped(String),
+    Failed(String),
+}
+
+// One part of one day run against one input
+pub struct Case {
+    pub run_code: RunCode<false>,
+    pub part: Part,
+}
+
+impl Case {
+    // e.g. 2020/day13/B/example:large
+    pub fn name(&self) -> String {
+        format!(
+            "{}/day{:02}/{}/{}",
+            self.run_code.year(),
+            self.run_code.problem(),
+            self.part,
+            self.run_code.input_kind()
+        )
+    }
+
+    // Why the case can't be checked, if its input or expected answer is missing
+    pub fn missing_data(&self, options: &RunOptions) -> Option<String> {
+        let input_path = self.run_code.input_path(options);
+        let answers_path = self.run_code.answers_path(options);
+        if !input_path.exists() || !answers_path.exists() {
+            return Some(format!(
+                "missing input or answer file for {}",
+                input_path.display()
+            ));
+        }
+        // A bad answer file is reported when the case is checked
+        let expected = ExpectedAnswers::load(&answers_path.to_string_lossy()).ok()?;
+        expected
+            .get(self.part)
+            .is_none()
+            .then(|| format!("no expected answer for part {}", self.part))
+    }
+
+    pub fn check(&self, options: &RunOptions) -> CaseOutcome {
+        if let Some(reason) = self.missing_data(options) {
+            return CaseOutcome::Skipped(reason);
+        }
+        let solution = match self.run_code.run(options) {
+            Ok(solution) => solution,
+            Err(e) => return CaseOutcome::Failed(e.message()),
+        };
+        let Some(outcome) = solution.outcome(self.part) else {
+            return CaseOutcome::Failed(format!("part {} was not run", self.part));
+        };
+        match (&outcome.result, &outcome.check) {
+            (Ok(Answer::NotImplemented), _) => {
+                CaseOutcome::Failed(format!("part {} is not implemented", self.part))
+            }
+            (Ok(answer), AnswerCheck::Wrong(expected)) => {
+                CaseOutcome::Failed(format!("got {answer}, expected {expected}"))
+            }
+            (Ok(_), _) => CaseOutcome::Passed,
+            (Err(e), _) => CaseOutcome::Failed(e.message()),
+        }
+    }
+}
+
+// The puzzle input and every example of each part of a day
+pub fn day_cases(year: u16, problem: usize, options: &RunOptions) -> Vec<Case> {
+    let mut cases = vec![];
+    for part in [Part::A, Part::B] {
+        let examples = RunCode::all_examples(year, problem).expand_examples(options);
+        cases.extend(
+            std::iter::once(RunCode::puzzle(year, problem))
+                .chain(examples)
+                .map(|run_code| Case {
+                    run_code: run_code.only(part),
+                    part,
+                }),
+        );
+    }
+    cases
+}
+
+// Every case of every registered day
+pub fn all_cases(options: &RunOptions) -> Vec<Case> {
+    days::DAYS
+        .iter()
+        .flat_map(|day| day_cases(day.year, day.problem, options))
+        .collect()
+}
+
+// Used by the tests that `new` writes into each day's module
+pub fn check_examples(year: u16, problem: usize, part: Part) {
+    let options = RunOptions::default();
+    let failures: Vec<_> = day_cases(year, problem, &options)
+        .into_iter()
+        .filter(|case| case.part == part && !case.run_code.is_puzzle_input())
+        .filter_map(|case| match case.check(&options) {
+            CaseOutcome::Failed(message) => Some(format!("{}: {message}", case.name())),
+            _ => None,
+        })
+        .collect();
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -0,0 +1,246 @@
+// Machine readable output of run results, with one record per day and part
+use serde::Serialize;
+use std::io;
+
+use crate::answers::AnswerCheck;
+use crate::io::{AocRunError, RunCode, RunOptions};
+use crate::problem::{Answer, Part};
+use crate::summary::RunStatus;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Csv,
+}
+
+impl OutputFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "text" => Some(OutputFormat::Text),
+            "json" => Some(OutputFormat::Json),
+            "csv" => Some(OutputFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+// Whether an answer matched the answer file, so that scripts can tell a verified answer
+// from one that was only solved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Check {
+    Correct,
+    Wrong,
+    Unverified,
+}
+
+impl From<&AnswerCheck> for Check {
+    fn from(check: &AnswerCheck) -> Self {
+        match check {
+            AnswerCheck::Correct => Check::Correct,
+            AnswerCheck::Wrong(_) => Check::Wrong,
+            AnswerCheck::Unverified => Check::Unverified,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Record {
+    pub year: u16,
+    pub day: usize,
+    pub part: Part,
+    pub input: String,
+    pub answer: Option<Answer>,
+    pub status: RunStatus,
+    // Only for parts that gave an answer
+    pub check: Option<Check>,
+    pub duration_ms: Option<f64>,
+    // Shared by both parts of a solution that parses its input once
+    pub parse_ms: Option<f64>,
+    // Only with --memory
+    pub peak_bytes: Option<u64>,
+    pub allocations: Option<u64>,
+    // Only with --memory, for solutions that parse their input once
+    pub parse_peak_bytes: Option<u64>,
+    pub parse_allocations: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl RunCode<true> {
+    pub fn records(&self, options: &RunOptions) -> Vec<Record> {
+        [Part::A, Part::B]
+            .into_iter()
+            .filter_map(|part| {
+                let outcome = self.outcome(part)?;
+                let (answer, check, error) = match &outcome.result {
+                    Ok(Answer::NotImplemented) => (Some(Answer::NotImplemented), None, None),
+                    Ok(answer) => (Some(answer.clone()), Some((&outcome.check).into()), None),
+                    Err(e) => (None, None, Some(e.message())),
+                };
+                Some(Record {
+                    year: self.year(),
+                    day: self.problem(),
+                    part,
+                    input: self.input_label(options),
+                    answer,
+                    status: RunStatus::from_outcome(outcome),
+                    check,
+                    duration_ms: Some(outcome.duration.as_secs_f64() * 1000.0),
+                    parse_ms: self.parse_duration().map(|d| d.as_secs_f64() * 1000.0),
+                    peak_bytes: outcome.memory.map(|m| m.peak_bytes),
+                    allocations: outcome.memory.map(|m| m.allocations),
+                    parse_peak_bytes: self.parse_memory().map(|m| m.peak_bytes),
+                    parse_allocations: self.parse_memory().map(|m| m.allocations),
+                    error,
+                })
+            })
+            .collect()
+    }
+}
+
+// Records for a run code, including those that failed before any part was solved
+pub fn records(
+    run_code: &RunCode<false>,
+    solution: &Result<RunCode<true>, AocRunError>,
+    options: &RunOptions,
+) -> Vec<Record> {
+    match solution {
+        Ok(sol) => sol.records(options),
+        Err(e) => run_code
+            .parts()
+            .map(|part| Record {
+                year: run_code.year(),
+                day: run_code.problem(),
+                part,
+                input: run_code.input_label(options),
+                answer: None,
+                status: RunStatus::from_error(e),
+                check: None,
+                duration_ms: None,
+                parse_ms: None,
+                peak_bytes: None,
+                allocations: None,
+                parse_peak_bytes: None,
+                parse_allocations: None,
+                error: Some(e.message()),
+            })
+            .collect(),
+    }
+}
+
+pub fn write_json(records: &[Record]) -> io::Result<()> {
+    serde_json::to_writer_pretty(io::stdout().lock(), records)?;
+    println!();
+    Ok(())
+}
+
+pub fn write_csv(records: &[Record]) -> io::Result<()> {
+    let mut writer = csv::Writer::from_writer(io::stdout().lock());
+    for record in records {
+        writer.serialize(record)?;
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::InputOverride;
+
+    fn record(status: RunStatus, answer: Option<Answer>, error: Option<&str>) -> Record {
+        Record {
+            year: 2020,
+            day: 13,
+            part: Part::B,
+            input: "example:large".to_string(),
+            check: answer.as_ref().map(|_| Check::Unverified),
+            answer,
+            status,
+            duration_ms: Some(1.5),
+            parse_ms: None,
+            peak_bytes: None,
+            allocations: None,
+            parse_peak_bytes: None,
+            parse_allocations: None,
+            error: error.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn serializes_json_without_colour() {
+        let error = AocRunError::NoFile("data/2020/inputs/13.txt".to_string());
+        let json = serde_json::to_string(&error).unwrap();
+        assert_eq!(
+            json,
+            "\"Could not find problem input at path data/2020/inputs/13.txt\""
+        );
+
+        let json =
+            serde_json::to_string(&record(RunStatus::MissingInput, None, Some("oops"))).unwrap();
+        assert_eq!(
+            json,
+            r#"{"year":2020,"day":13,"part":"B","input":"example:large","answer":null,"status":"missing_input","check":null,"duration_ms":1.5,"parse_ms":null,"peak_bytes":null,"allocations":null,"parse_peak_bytes":null,"parse_allocations":null,"error":"oops"}"#
+        );
+    }
+
+    #[test]
+    fn serializes_run_code() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let examples = dir.path().join("2020/examples");
+        let answers = dir.path().join("2020/answers/examples");
+        std::fs::create_dir_all(&examples).unwrap();
+        std::fs::create_dir_all(&answers).unwrap();
+        std::fs::write(examples.join("01.txt"), "1721\n979\n366\n299\n675\n1456\n").unwrap();
+        std::fs::write(answers.join("01.txt"), "A: 514579\n").unwrap();
+        let options = RunOptions {
+            data_dir: dir.path().to_path_buf(),
+            ..RunOptions::default()
+        };
+        let solution = RunCode::example(2020, 1, None).run(&options).unwrap();
+        let json = serde_json::to_value(solution.records(&options)).unwrap();
+        let records = json.as_array().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["part"], "A");
+        assert_eq!(records[0]["input"], "example");
+        assert_eq!(records[0]["answer"], 514579);
+        assert_eq!(records[0]["status"], "solved");
+        assert_eq!(records[0]["check"], "correct");
+        assert_eq!(records[1]["answer"], 241861950);
+        assert_eq!(records[1]["check"], "unverified");
+        assert!(records[1]["duration_ms"].is_f64());
+
+        // An input given with --input is reported instead of the puzzle input it replaced
+        let input = examples.join("01.txt");
+        let options = RunOptions {
+            input: Some(InputOverride::File(input.clone())),
+            ..options
+        };
+        let run_code = RunCode::puzzle(2020, 1);
+        let overridden = super::records(&run_code, &run_code.run(&options), &options);
+        assert_eq!(overridden[0].input, format!("file:{}", input.display()));
+        assert_eq!(overridden[0].check, Some(Check::Unverified));
+    }
+
+    #[test]
+    fn serializes_csv() {
+        let mut writer = csv::Writer::from_writer(vec![]);
+        writer
+            .serialize(record(
+                RunStatus::Solved,
+                Some(Answer::Integer(1068781)),
+                None,
+            ))
+            .unwrap();
+        writer
+            .serialize(record(RunStatus::Error, None, Some("bad, input")))
+            .unwrap();
+        let csv = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        assert_eq!(
+            csv,
+            "year,day,part,input,answer,status,check,duration_ms,parse_ms,peak_bytes,allocations,parse_peak_bytes,parse_allocations,error\n\
+             2020,13,B,example:large,1068781,solved,unverified,1.5,,,,,,\n\
+             2020,13,B,example:large,,error,,1.5,,,,,,\"bad, input\"\n"
+        );
+    }
+}
@@ -0,0 +1,225 @@
+// Writes the skeleton of a new day into src/days and registers it in src/days/mod.rs.
+// The new day's title, parts and tags are left for the author to fill in.
+use colored::Colorize;
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::io::DEFAULT_YEAR;
+
+const TEMPLATE: &str = r#"use crate::context::Context;
+use crate::problem::{Answer, ProblemSolution, SolveResult};
+#[allow(unused_imports)]
+use aoc_parse::{parser, prelude::*, ParseError};
+pub struct Solution {}
+
+fn parse_input(input: &str) -> Result<Vec<usize>, ParseError> {
+    let p = parser!(lines(usize));
+    p.parse(input)
+}
+
+impl ProblemSolution for Solution {
+    fn solve_a(&self, input: &str, _ctx: &Context) -> SolveResult {
+        let _input = parse_input(input)?;
+        Ok(Answer::NotImplemented)
+    }
+
+    fn solve_b(&self, input: &str, _ctx: &Context) -> SolveResult {
+        let _input = parse_input(input)?;
+        Ok(Answer::NotImplemented)
+    }
+}
+
+// Checks every example in data/YEAR/examples that has an answer file
+#[cfg(test)]
+mod tests {
+    use crate::problem::Part;
+    use crate::regression::check_examples;
+
+    #[test]
+    fn a_examples() {
+        check_examples(YEAR, PROBLEM, Part::A)
+    }
+
+    #[test]
+    fn b_examples() {
+        check_examples(YEAR, PROBLEM, Part::B)
+    }
+}
+"#;
+
+// The body of a day that has not been started, which is safe to replace
+const STUB: &str = "impl ProblemSolution for Solution {}";
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    AlreadyImplemented(String),
+    BadRegistry(String),
+    Io(String),
+}
+
+impl Display for ScaffoldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let explanation = match self {
+            ScaffoldError::AlreadyImplemented(path) => {
+                format!("{path} is already implemented, refusing to overwrite it")
+            }
+            ScaffoldError::BadRegistry(path) => {
+                format!("Could not find where to register the day in {path}")
+            }
+            ScaffoldError::Io(msg) => format!("Could not write the new day: {msg}"),
+        };
+        explanation.bold().red().fmt(f)
+    }
+}
+
+// Days from other years get the year in their module name, so that they don't clash
+pub fn module_name(year: u16, problem: usize) -> String {
+    if year == DEFAULT_YEAR {
+        format!("day{problem:02}")
+    } else {
+        format!("y{year}_day{problem:02}")
+    }
+}
+
+// Writes the template for a day, replacing a stub but never a day that has been started.
+// Returns the path of the new file.
+pub fn new_day(days_dir: &Path, year: u16, problem: usize) -> Result<PathBuf, ScaffoldError> {
+    let module = module_name(year, problem);
+    let path = days_dir.join(format!("{module}.rs"));
+    let io_error = |e: std::io::Error| ScaffoldError::Io(e.to_string());
+    if let Ok(existing) = fs::read_to_string(&path) {
+        if !existing.contains(STUB) {
+            return Err(ScaffoldError::AlreadyImplemented(
+                path.display().to_string(),
+            ));
+        }
+    }
+
+    let registry_path = days_dir.join("mod.rs");
+    let registry = fs::read_to_string(&registry_path).map_err(io_error)?;
+    let registry = register(&registry, year, problem)
+        .ok_or_else(|| ScaffoldError::BadRegistry(registry_path.display().to_string()))?;
+
+    let contents = TEMPLATE
+        .replace("YEAR", &year.to_string())
+        .replace("PROBLEM", &problem.to_string());
+    fs::write(&path, contents).map_err(io_error)?;
+    fs::write(&registry_path, registry).map_err(io_error)?;
+    Ok(path)
+}
+
+// Adds an entry for the day to the register_days! table, keeping it in order.
+// Returns None if the registry does not look as expected.
+fn register(registry: &str, year: u16, problem: usize) -> Option<String> {
+    let mut lines: Vec<String> = registry.lines().map(str::to_string).collect();
+    let entry_keys: Vec<_> = lines.iter().map(|line| parse_entry(line)).collect();
+    if entry_keys.contains(&Some((year, problem))) {
+        return Some(registry.to_string());
+    }
+    let table_start = lines
+        .iter()
+        .position(|line| line.starts_with("register_days! {"))?;
+    let table_end = table_start + lines[table_start..].iter().position(|line| line == "}")?;
+    let index = entry_keys
+        .iter()
+        .position(|key| key.is_some_and(|key| key > (year, problem)))
+        .unwrap_or(table_end);
+    let module = module_name(year, problem);
+    let entry = [
+        format!("    ({year}, {problem}) => {module} {{"),
+        "        title: \"\",".to_string(),
+        "        parts: \"\",".to_string(),
+        "        tags: [],".to_string(),
+        "    },".to_string(),
+    ];
+    lines.splice(index..index, entry);
+    Some(lines.join("\n") + "\n")
+}
+
+// Reads the (year, problem) of an entry such as `(2020, 1) => day01 {`
+fn parse_entry(line: &str) -> Option<(u16, usize)> {
+    let (key, _) = line.trim_start().strip_prefix('(')?.split_once(") =>")?;
+    let (year, problem) = key.split_once(", ")?;
+    Some((year.parse().ok()?, problem.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const REGISTRY: &str = r#"use crate::problem::ProblemSolution;
+
+register_days! {
+    (2020, 1) => day01 {
+        title: "Report Repair",
+        parts: "ab",
+        tags: ["search"],
+    },
+    (2020, 3) => day03 {
+        title: "Toboggan Trajectory",
+        parts: "",
+        tags: ["grid"],
+    },
+}
+"#;
+
+    #[test]
+    fn registers_in_order() {
+        let registry = register(REGISTRY, 2020, 2).unwrap();
+        assert!(registry.contains(
+            r#"        tags: ["search"],
+    },
+    (2020, 2) => day02 {
+        title: "",
+        parts: "",
+        tags: [],
+    },
+    (2020, 3) => day03 {"#
+        ));
+
+        let registry = register(&registry, 2021, 5).unwrap();
+        assert!(registry.ends_with(
+            r#"    (2021, 5) => y2021_day05 {
+        title: "",
+        parts: "",
+        tags: [],
+    },
+}
+"#
+        ));
+
+        // Registering twice changes nothing
+        assert_eq!(register(&registry, 2021, 5).unwrap(), registry);
+        assert_eq!(register(&registry, 2020, 3).unwrap(), registry);
+    }
+
+    #[test]
+    fn replaces_stubs_but_not_implemented_days() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("mod.rs"), REGISTRY).unwrap();
+        fs::write(
+            dir.path().join("day03.rs"),
+            "use crate::problem::ProblemSolution;\npub struct Solution {}\n\n".to_string()
+                + STUB
+                + "\n",
+        )
+        .unwrap();
+        let path = new_day(dir.path(), 2020, 3).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(contents.contains("fn parse_input(input: &str)"));
+        assert!(contents.contains("check_examples(2020, 3, Part::A)"));
+
+        // The template has been written, so the day now counts as started
+        assert!(matches!(
+            new_day(dir.path(), 2020, 3),
+            Err(ScaffoldError::AlreadyImplemented(_))
+        ));
+        fs::write(dir.path().join("day01.rs"), "fn solve_a() {}\n").unwrap();
+        assert!(matches!(
+            new_day(dir.path(), 2020, 1),
+            Err(ScaffoldError::AlreadyImplemented(_))
+        ));
+    }
+}
@@ -0,0 +1,449 @@
+// Submits answers to the Advent of Code website.
+// Every verdict is recorded in a ledger, data/<year>/attempts.txt, so that known wrong
+// answers are never sent twice and new answers can be checked against earlier bounds.
+use aoc_parse::{parser, prelude::*};
+use colored::Colorize;
+use std::fmt::Display;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::fetch::{FetchError, Fetcher};
+use crate::io::AocRunError;
+use crate::problem::Part;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    // Wrong, without a hint about which way
+    Wrong,
+}
+
+impl Verdict {
+    fn label(&self) -> &'static str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::TooHigh => "too-high",
+            Verdict::TooLow => "too-low",
+            Verdict::Wrong => "wrong",
+        }
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let verdict = match self {
+            Verdict::Correct => "That's the right answer!".bold().green(),
+            Verdict::TooHigh => "Wrong, the answer is too high".bold().red(),
+            Verdict::TooLow => "Wrong, the answer is too low".bold().red(),
+            Verdict::Wrong => "Wrong answer".bold().red(),
+        };
+        verdict.fmt(f)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SubmitResponse {
+    Verdict(Verdict),
+    // Submitted too soon after a previous attempt, None if the wait could not be read
+    Wait(Option<Duration>),
+    // The part has already been solved, or is not unlocked yet
+    WrongLevel,
+    Unrecognised(String),
+}
+
+impl SubmitResponse {
+    // Reads the verdict out of the page returned after posting an answer
+    pub fn parse(page: &str) -> Self {
+        let text = article_text(page);
+        if text.contains("You gave an answer too recently") {
+            SubmitResponse::Wait(parse_wait(&text))
+        } else if text.contains("That's the right answer") {
+            SubmitResponse::Verdict(Verdict::Correct)
+        } else if text.contains("your answer is too high") {
+            SubmitResponse::Verdict(Verdict::TooHigh)
+        } else if text.contains("your answer is too low") {
+            SubmitResponse::Verdict(Verdict::TooLow)
+        } else if text.contains("That's not the right answer") {
+            SubmitResponse::Verdict(Verdict::Wrong)
+        } else if text.contains("You don't seem to be solving the right level") {
+            SubmitResponse::WrongLevel
+        } else {
+            SubmitResponse::Unrecognised(text)
+        }
+    }
+}
+
+impl Display for SubmitResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubmitResponse::Verdict(verdict) => verdict.fmt(f),
+            SubmitResponse::Wait(Some(wait)) => {
+                let message = format!("Answered too recently, wait {wait:?} before trying again");
+                message.yellow().fmt(f)
+            }
+            SubmitResponse::Wait(None) => "Answered too recently, wait before trying again"
+                .yellow()
+                .fmt(f),
+            SubmitResponse::WrongLevel => "This part is already solved or not unlocked yet"
+                .yellow()
+                .fmt(f),
+            SubmitResponse::Unrecognised(text) => {
+                write!(
+                    f,
+                    "{}\n{text}",
+                    "Could not understand the response:".yellow()
+                )
+            }
+        }
+    }
+}
+
+// The message is the text of the page's <article>, with the markup stripped
+fn article_text(page: &str) -> String {
+    let article = page
+        .split_once("<article")
+        .and_then(|(_, rest)| rest.split_once('>'))
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+        .map_or(page, |(article, _)| article);
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// Reads e.g. "You have 1m 30s left to wait"
+fn parse_wait(text: &str) -> Option<Duration> {
+    let (_, rest) = text.split_once("You have ")?;
+    let (wait, _) = rest.split_once(" left to wait")?;
+    let mut seconds = 0;
+    for amount in wait.split_whitespace() {
+        let (value, unit) = amount.split_at(amount.find(|c: char| !c.is_ascii_digit())?);
+        let value: u64 = value.parse().ok()?;
+        seconds += match unit {
+            "h" => value * 3600,
+            "m" => value * 60,
+            "s" => value,
+            _ => return None,
+        };
+    }
+    Some(Duration::from_secs(seconds))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    pub problem: usize,
+    pub part: Part,
+    pub verdict: Verdict,
+    pub answer: String,
+}
+
+// Attempts are stored one per line, e.g.
+// 4 A too-high 1234
+pub struct Ledger {
+    path: PathBuf,
+    attempts: Vec<Attempt>,
+}
+
+impl Ledger {
+    pub fn path(data_dir: &Path, year: u16) -> PathBuf {
+        data_dir.join(year.to_string()).join("attempts.txt")
+    }
+
+    // A missing file just means nothing has been submitted yet
+    pub fn load(path: PathBuf) -> Result<Self, AocRunError> {
+        let Ok(contents) = fs::read_to_string(&path) else {
+            return Ok(Self {
+                path,
+                attempts: vec![],
+            });
+        };
+        let part_parser = parser!({"A" => Part::A, "B" => Part::B});
+        let verdict_parser = parser!({
+            "correct" => Verdict::Correct,
+            "too-high" => Verdict::TooHigh,
+            "too-low" => Verdict::TooLow,
+            "wrong" => Verdict::Wrong,
+        });
+        let p = parser!(lines(
+            usize " " part_parser " " verdict_parser " " string(any_char+)
+        ));
+        let attempts = p
+            .parse(&contents)
+            .map_err(|_e| AocRunError::BadLedgerFile(path.display().to_string()))?
+            .into_iter()
+            .map(|(problem, part, verdict, answer)| Attempt {
+                problem,
+                part,
+                verdict,
+                answer,
+            })
+            .collect();
+        Ok(Self { path, attempts })
+    }
+
+    fn attempts_for(&self, problem: usize, part: Part) -> impl Iterator<Item = &Attempt> {
+        self.attempts
+            .iter()
+            .filter(move |attempt| attempt.problem == problem && attempt.part == part)
+    }
+
+    fn previous_verdict(&self, problem: usize, part: Part, answer: &str) -> Option<Verdict> {
+        self.attempts_for(problem, part)
+            .find(|attempt| attempt.answer == answer.trim())
+            .map(|attempt| attempt.verdict)
+    }
+
+    fn correct_answer(&self, problem: usize, part: Part) -> Option<&str> {
+        self.attempts_for(problem, part)
+            .find(|attempt| attempt.verdict == Verdict::Correct)
+            .map(|attempt| attempt.answer.as_str())
+    }
+
+    // Explains why a numeric answer can't be right given earlier too high or too low verdicts
+    pub fn bounds_warning(&self, problem: usize, part: Part, answer: &str) -> Option<String> {
+        let answer: i128 = answer.trim().parse().ok()?;
+        let bound = |verdict| {
+            self.attempts_for(problem, part)
+                .filter(move |attempt| attempt.verdict == verdict)
+                .filter_map(|attempt| attempt.answer.parse::<i128>().ok())
+        };
+        if let Some(too_high) = bound(Verdict::TooHigh).filter(|high| answer >= *high).min() {
+            return Some(format!(
+                "{answer} is not below {too_high}, which was too high"
+            ));
+        }
+        if let Some(too_low) = bound(Verdict::TooLow).filter(|low| answer <= *low).max() {
+            return Some(format!(
+                "{answer} is not above {too_low}, which was too low"
+            ));
+        }
+        None
+    }
+
+    pub fn record(&mut self, attempt: Attempt) -> std::io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(
+            file,
+            "{} {} {} {}",
+            attempt.problem,
+            attempt.part,
+            attempt.verdict.label(),
+            attempt.answer
+        )?;
+        self.attempts.push(attempt);
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum SubmitError {
+    AlreadySolved(String),
+    KnownWrong(Verdict),
+    Fetch(FetchError),
+    Ledger(String),
+}
+
+impl From<FetchError> for SubmitError {
+    fn from(value: FetchError) -> Self {
+        SubmitError::Fetch(value)
+    }
+}
+
+impl Display for SubmitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let explanation = match self {
+            SubmitError::AlreadySolved(answer) => {
+                format!("Not submitting, this part was already solved with {answer}")
+            }
+            SubmitError::KnownWrong(verdict) => format!(
+                "Not submitting, this answer was already tried and was {}",
+                verdict.label()
+            ),
+            SubmitError::Fetch(e) => return e.fmt(f),
+            SubmitError::Ledger(msg) => format!("Could not record the attempt: {msg}"),
+        };
+        explanation.bold().red().fmt(f)
+    }
+}
+
+// Posts an answer unless the ledger shows it is pointless, then records the verdict
+pub fn submit_answer(
+    fetcher: &Fetcher,
+    ledger: &mut Ledger,
+    year: u16,
+    problem: usize,
+    part: Part,
+    answer: &str,
+) -> Result<SubmitResponse, SubmitError> {
+    let answer = answer.trim();
+    if let Some(correct) = ledger.correct_answer(problem, part) {
+        return Err(SubmitError::AlreadySolved(correct.to_string()));
+    }
+    if let Some(verdict) = ledger.previous_verdict(problem, part, answer) {
+        return Err(SubmitError::KnownWrong(verdict));
+    }
+    let level = match part {
+        Part::A => "1",
+        Part::B => "2",
+    };
+    let page = fetcher.post_form(
+        &format!("/{year}/day/{problem}/answer"),
+        &[("level", level), ("answer", answer)],
+    )?;
+    let response = SubmitResponse::parse(&page);
+    if let SubmitResponse::Verdict(verdict) = response {
+        ledger
+            .record(Attempt {
+                problem,
+                part,
+                verdict,
+                answer: answer.to_string(),
+            })
+            .map_err(|e| SubmitError::Ledger(e.to_string()))?;
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::TestServer;
+    use tempfile::TempDir;
+
+    fn page(message: &str) -> String {
+        format!("<html><body><main>\n<article><p>{message}</p></article>\n</main></body></html>")
+    }
+
+    fn setup(responses: Vec<(u16, String)>) -> (TestServer, TempDir, Fetcher, Ledger) {
+        let server = TestServer::start(responses);
+        let dir = TempDir::new().unwrap();
+        let fetcher = Fetcher::new(
+            &server.url(),
+            "abc123".to_string(),
+            dir.path().join(".last_fetch"),
+            Duration::ZERO,
+        );
+        let ledger = Ledger::load(Ledger::path(dir.path(), 2020)).unwrap();
+        (server, dir, fetcher, ledger)
+    }
+
+    #[test]
+    fn parses_responses() {
+        let cases = [
+            (
+                "That's the right answer!  You are <span class=\"day-success\">one gold star</span> closer.",
+                SubmitResponse::Verdict(Verdict::Correct),
+            ),
+            (
+                "That's not the right answer; your answer is too high.  If you're stuck, make sure you're using the full input data.",
+                SubmitResponse::Verdict(Verdict::TooHigh),
+            ),
+            (
+                "That's not the right answer; your answer is too low.",
+                SubmitResponse::Verdict(Verdict::TooLow),
+            ),
+            (
+                "That's not the right answer.  If you're stuck, make sure you're using the full input data.",
+                SubmitResponse::Verdict(Verdict::Wrong),
+            ),
+            (
+                "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 5s left to wait.",
+                SubmitResponse::Wait(Some(Duration::from_secs(65))),
+            ),
+            (
+                "You don't seem to be solving the right level.  Did you already complete it?",
+                SubmitResponse::WrongLevel,
+            ),
+        ];
+        for (message, expected) in cases {
+            assert_eq!(SubmitResponse::parse(&page(message)), expected, "{message}");
+        }
+    }
+
+    #[test]
+    fn posts_answer_and_records_verdict() {
+        let (server, dir, fetcher, mut ledger) = setup(vec![(
+            200,
+            page("That's not the right answer; your answer is too low."),
+        )]);
+        let response = submit_answer(&fetcher, &mut ledger, 2020, 4, Part::B, "123\n").unwrap();
+        assert_eq!(response, SubmitResponse::Verdict(Verdict::TooLow));
+
+        let request = server.requests().remove(0);
+        assert!(request.starts_with("POST /2020/day/4/answer "));
+        assert!(request.ends_with("level=2&answer=123"));
+
+        let ledger = Ledger::load(Ledger::path(dir.path(), 2020)).unwrap();
+        assert_eq!(
+            ledger.previous_verdict(4, Part::B, "123"),
+            Some(Verdict::TooLow)
+        );
+    }
+
+    #[test]
+    fn refuses_known_wrong_answer() {
+        let (server, _dir, fetcher, mut ledger) =
+            setup(vec![(200, page("That's not the right answer."))]);
+        submit_answer(&fetcher, &mut ledger, 2020, 1, Part::A, "5").unwrap();
+        let second = submit_answer(&fetcher, &mut ledger, 2020, 1, Part::A, "5");
+        assert!(matches!(
+            second,
+            Err(SubmitError::KnownWrong(Verdict::Wrong))
+        ));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn refuses_solved_part() {
+        let (server, _dir, fetcher, mut ledger) =
+            setup(vec![(200, page("That's the right answer!"))]);
+        submit_answer(&fetcher, &mut ledger, 2020, 1, Part::A, "514579").unwrap();
+        let second = submit_answer(&fetcher, &mut ledger, 2020, 1, Part::A, "42");
+        assert!(matches!(second, Err(SubmitError::AlreadySolved(_))));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn does_not_record_wait() {
+        let message = "You gave an answer too recently.  You have 30s left to wait.";
+        let (_server, dir, fetcher, mut ledger) = setup(vec![(200, page(message))]);
+        let response = submit_answer(&fetcher, &mut ledger, 2020, 1, Part::A, "5").unwrap();
+        assert_eq!(
+            response,
+            SubmitResponse::Wait(Some(Duration::from_secs(30)))
+        );
+        assert!(!Ledger::path(dir.path(), 2020).exists());
+    }
+
+    #[test]
+    fn warns_outside_known_bounds() {
+        let dir = TempDir::new().unwrap();
+        let path = Ledger::path(dir.path(), 2020);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "9 A too-high 500\n9 A too-low 100\n9 B wrong 7\n").unwrap();
+        let ledger = Ledger::load(path).unwrap();
+        assert!(ledger.bounds_warning(9, Part::A, "600").is_some());
+        assert!(ledger.bounds_warning(9, Part::A, "500").is_some());
+        assert!(ledger.bounds_warning(9, Part::A, "50").is_some());
+        assert!(ledger.bounds_warning(9, Part::A, "250").is_none());
+        assert!(ledger.bounds_warning(9, Part::B, "600").is_none());
+        assert!(ledger.bounds_warning(9, Part::A, "abc").is_none());
+    }
+}
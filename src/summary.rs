@@ -0,0 +1,215 @@
+use colored::{ColoredString, Colorize};
+use serde::Serialize;
+use std::fmt::Display;
+use std::time::Duration;
+
+use crate::answers::AnswerCheck;
+use crate::io::{AocRunError, PartOutcome, RunCode};
+use crate::problem::{Answer, Part};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Solved,
+    Wrong,
+    Todo,
+    MissingInput,
+    Error,
+    Panic,
+    Timeout,
+    // The part was not requested by the run code
+    Skipped,
+}
+
+impl RunStatus {
+    // Every status that is worth counting in the totals
+    const COUNTED: [RunStatus; 7] = [
+        RunStatus::Solved,
+        RunStatus::Wrong,
+        RunStatus::Todo,
+        RunStatus::MissingInput,
+        RunStatus::Error,
+        RunStatus::Panic,
+        RunStatus::Timeout,
+    ];
+
+    pub fn from_outcome(outcome: &PartOutcome) -> Self {
+        match &outcome.result {
+            Ok(Answer::NotImplemented) => RunStatus::Todo,
+            Ok(_) if matches!(outcome.check, AnswerCheck::Wrong(_)) => RunStatus::Wrong,
+            Ok(_) => RunStatus::Solved,
+            Err(e) => RunStatus::from_error(e),
+        }
+    }
+
+    pub fn from_error(error: &AocRunError) -> Self {
+        match error {
+            AocRunError::NoFile(_) => RunStatus::MissingInput,
+            AocRunError::Panicked(..) => RunStatus::Panic,
+            AocRunError::TimedOut(..) => RunStatus::Timeout,
+            _ => RunStatus::Error,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            RunStatus::Solved => "solved",
+            RunStatus::Wrong => "wrong",
+            RunStatus::Todo => "TODO",
+            RunStatus::MissingInput => "missing input",
+            RunStatus::Error => "error",
+            RunStatus::Panic => "panic",
+            RunStatus::Timeout => "timeout",
+            RunStatus::Skipped => "-",
+        }
+    }
+
+    // Pads before colouring so that escape codes don't upset the column widths
+    fn colored(&self, width: usize) -> ColoredString {
+        let padded = format!("{:<width$}", self.label());
+        match self {
+            RunStatus::Solved => padded.green(),
+            RunStatus::Wrong => padded.bold().red(),
+            RunStatus::Todo => padded.dimmed(),
+            RunStatus::MissingInput => padded.yellow(),
+            RunStatus::Error => padded.red(),
+            RunStatus::Panic => padded.bold().magenta(),
+            RunStatus::Timeout => padded.bold().yellow(),
+            RunStatus::Skipped => padded.dimmed(),
+        }
+    }
+}
+
+struct SummaryRow {
+    label: String,
+    status_a: RunStatus,
+    status_b: RunStatus,
+}
+
+#[derive(Default)]
+pub struct Summary {
+    rows: Vec<SummaryRow>,
+    // Time spent solving parts, added up across every row
+    solve_time: Duration,
+    // Time taken by the whole run, including loading inputs, once it has finished
+    wall_clock: Option<(Duration, usize)>,
+}
+
+impl Summary {
+    pub fn record(
+        &mut self,
+        run_code: &RunCode<false>,
+        solution: &Result<RunCode<true>, AocRunError>,
+    ) {
+        let (status_a, status_b) = match solution {
+            Ok(sol) => {
+                self.solve_time += [Part::A, Part::B]
+                    .into_iter()
+                    .filter_map(|part| sol.outcome(part))
+                    .map(|outcome| outcome.duration)
+                    .sum::<Duration>();
+                let status = |part| {
+                    sol.outcome(part)
+                        .map(RunStatus::from_outcome)
+                        .unwrap_or(RunStatus::Skipped)
+                };
+                (status(Part::A), status(Part::B))
+            }
+            // Only the parts that were asked for failed
+            Err(e) => {
+                let status = |part| {
+                    if run_code.parts().any(|p| p == part) {
+                        RunStatus::from_error(e)
+                    } else {
+                        RunStatus::Skipped
+                    }
+                };
+                (status(Part::A), status(Part::B))
+            }
+        };
+        self.rows.push(SummaryRow {
+            label: run_code.label(),
+            status_a,
+            status_b,
+        });
+    }
+
+    pub fn set_wall_clock(&mut self, wall_clock: Duration, jobs: usize) {
+        self.wall_clock = Some((wall_clock, jobs));
+    }
+
+    fn count(&self, status: RunStatus) -> usize {
+        self.rows
+            .iter()
+            .flat_map(|row| [row.status_a, row.status_b])
+            .filter(|s| *s == status)
+            .count()
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = RunStatus::COUNTED
+            .iter()
+            .map(|s| s.label().len())
+            .max()
+            .unwrap();
+        writeln!(f, "{}", "Summary".bold())?;
+        let label_width = self
+            .rows
+            .iter()
+            .map(|row| row.label.len())
+            .max()
+            .unwrap_or(0)
+            .max(3);
+        writeln!(
+            f,
+            "{:>label_width$} | {:<width$} | {:<width$}",
+            "Day", "A", "B"
+        )?;
+        writeln!(
+            f,
+            "{:-<label_width$}-+-{:-<width$}-+-{:-<width$}",
+            "", "", ""
+        )?;
+        for row in self.rows.iter() {
+            writeln!(
+                f,
+                "{:>label_width$} | {} | {}",
+                row.label,
+                row.status_a.colored(width),
+                row.status_b.colored(width)
+            )?;
+        }
+        let totals: Vec<_> = RunStatus::COUNTED
+            .iter()
+            .map(|s| format!("{} {}", self.count(*s), s.label()))
+            .collect();
+        writeln!(f, "{}", totals.join(", "))?;
+        write!(f, "Parts took {:.2?} in total", self.solve_time)?;
+        if let Some((wall_clock, jobs)) = self.wall_clock {
+            let threads = if jobs == 1 { "thread" } else { "threads" };
+            write!(f, ", the run took {wall_clock:.2?} on {jobs} {threads}")?;
+        }
+        writeln!(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_only_requested_parts() {
+        let mut summary = Summary::default();
+        for code in ["5a", "6a"] {
+            let run_code = RunCode::parse_codes(code, 2020).unwrap().remove(0);
+            let error = AocRunError::NoFile(format!("data/2020/inputs/0{code}.txt"));
+            summary.record(&run_code, &Err(error));
+        }
+        assert_eq!(summary.count(RunStatus::MissingInput), 2);
+        assert_eq!(summary.count(RunStatus::Skipped), 2);
+        assert_eq!(summary.rows[1].label, "6");
+        assert_eq!(summary.rows[1].status_b, RunStatus::Skipped);
+    }
+}
@@ -0,0 +1,156 @@
+// Support for --watch, which re-runs whenever an input or answer file changes
+use colored::Colorize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::io::RunCode;
+use crate::problem::Part;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// Moves the cursor to the top left after clearing the terminal
+pub const CLEAR_SCREEN: &str = "\x1B[2J\x1B[1;1H";
+
+// What we know about a file without reading it, None if it doesn't exist
+type Fingerprint = Option<(Option<SystemTime>, u64)>;
+
+fn fingerprint(path: &PathBuf) -> Fingerprint {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok(), metadata.len()))
+}
+
+pub struct Watcher {
+    paths: Vec<PathBuf>,
+    fingerprints: Vec<Fingerprint>,
+}
+
+impl Watcher {
+    pub fn new(mut paths: Vec<PathBuf>) -> Self {
+        paths.sort();
+        paths.dedup();
+        let fingerprints = paths.iter().map(fingerprint).collect();
+        Self {
+            paths,
+            fingerprints,
+        }
+    }
+
+    // Whether any file has been created, removed or modified since the last check
+    pub fn changed(&mut self) -> bool {
+        let fingerprints: Vec<_> = self.paths.iter().map(fingerprint).collect();
+        let changed = fingerprints != self.fingerprints;
+        self.fingerprints = fingerprints;
+        changed
+    }
+
+    pub fn wait_for_change(&mut self) {
+        while !self.changed() {
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+// The answers from the previous run, so that each run can show what changed
+#[derive(Default)]
+pub struct AnswerHistory {
+    previous: HashMap<(String, Part), Option<String>>,
+}
+
+impl AnswerHistory {
+    // Describes each answer that differs from the last run of the same run code
+    pub fn changes(&mut self, label: String, solution: &RunCode<true>) -> Vec<String> {
+        let mut changes = vec![];
+        for part in [Part::A, Part::B] {
+            let Some(outcome) = solution.outcome(part) else {
+                continue;
+            };
+            let answer = outcome
+                .result
+                .as_ref()
+                .ok()
+                .filter(|answer| answer.is_implemented())
+                .map(|answer| answer.to_string());
+            let previous = self.previous.insert((label.clone(), part), answer.clone());
+            if let Some(previous) = previous {
+                changes.extend(describe_change(
+                    part,
+                    previous.as_deref(),
+                    answer.as_deref(),
+                ));
+            }
+        }
+        changes
+    }
+}
+
+fn describe_change(part: Part, previous: Option<&str>, answer: Option<&str>) -> Option<String> {
+    if previous == answer {
+        return None;
+    }
+    let (previous, answer) = (
+        previous.unwrap_or("no answer"),
+        answer.unwrap_or("no answer"),
+    );
+    if !previous.contains('\n') && !answer.contains('\n') {
+        return Some(format!("{part} : changed from {previous} to {answer}"));
+    }
+    let mut change = format!("{part} : changed");
+    for line in previous.lines() {
+        change.push_str(&format!("\n- {line}"));
+    }
+    for line in answer.lines() {
+        change.push_str(&format!("\n+ {line}"));
+    }
+    Some(change)
+}
+
+pub fn print_changes(changes: &[String]) {
+    for change in changes {
+        println!("{}", change.yellow());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn notices_changed_files() {
+        let dir = TempDir::new().unwrap();
+        let input = dir.path().join("07.txt");
+        let answers = dir.path().join("answers.txt");
+        fs::write(&input, "light red bags contain 1 bright white bag.\n").unwrap();
+        let mut watcher = Watcher::new(vec![input.clone(), answers.clone(), input.clone()]);
+        assert!(!watcher.changed());
+
+        fs::write(&input, "light red bags contain 2 bright white bags.\n").unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+
+        fs::write(&answers, "A: 4\n").unwrap();
+        assert!(watcher.changed());
+        fs::remove_file(&answers).unwrap();
+        assert!(watcher.changed());
+    }
+
+    #[test]
+    fn describes_changed_answers() {
+        assert_eq!(describe_change(Part::A, Some("4"), Some("4")), None);
+        assert_eq!(
+            describe_change(Part::A, Some("4"), Some("5")).unwrap(),
+            "A : changed from 4 to 5"
+        );
+        assert_eq!(
+            describe_change(Part::B, Some("4"), None).unwrap(),
+            "B : changed from 4 to no answer"
+        );
+        assert_eq!(
+            describe_change(Part::B, Some("#.\n.#"), Some("#.\n##")).unwrap(),
+            "B : changed\n- #.\n- .#\n+ #.\n+ ##"
+        );
+    }
+}
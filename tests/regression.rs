@@ -0,0 +1,23 @@
+// One test per day, part and input in data/, see aoc_2020::regression.
+// Cases whose input or answer is missing show up as ignored.
+use aoc_2020::io::RunOptions;
+use aoc_2020::regression::{self, CaseOutcome};
+use libtest_mimic::{Arguments, Failed, Trial};
+
+fn main() {
+    let args = Arguments::from_args();
+    let options = RunOptions::default();
+    let trials = regression::all_cases(&options)
+        .into_iter()
+        .map(|case| {
+            let missing_data = case.missing_data(&options).is_some();
+            let options = options.clone();
+            Trial::test(case.name(), move || match case.check(&options) {
+                CaseOutcome::Passed | CaseOutcome::Skipped(_) => Ok(()),
+                CaseOutcome::Failed(message) => Err(Failed::from(message)),
+            })
+            .with_ignored_flag(missing_data)
+        })
+        .collect();
+    libtest_mimic::run(&args, trials).exit();
+}